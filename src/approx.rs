@@ -0,0 +1,298 @@
+//! Approximate pattern matching with mismatches or edits.
+//!
+//! The suffix array is searched by backtracking: ranges of suffix array indices are split by the next symbol
+//! and a branch is dropped as soon as it exceeds the allowed distance, so each distinct substring is visited once.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::sa::SuffixArray;
+use crate::search::{narrow, Children, Positions};
+
+/// Operation of an alignment, transforming the pattern into the matched text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditOp {
+    Match,
+    Mismatch,
+    /// Symbol of the text not in the pattern.
+    Insertion,
+    /// Symbol of the pattern not in the text.
+    Deletion,
+}
+
+/// An occurrence of the pattern within the allowed distance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApproximateMatch {
+    /// Text position of the occurrence.
+    pub position: usize,
+    /// Length of the occurrence in the text.
+    pub length: usize,
+    /// Number of operations other than [EditOp::Match].
+    pub distance: usize,
+    /// Operations from the start of the pattern and the occurrence.
+    pub alignment: Vec<EditOp>,
+}
+
+/// Search for the pattern P in the string T with at most `max_mismatches` mismatches (Hamming distance).  
+/// Input: Vector of symbols & vector of pattern string, its suffix array and the maximum mismatches.  
+/// Output: Occurrences of the length of the pattern, in order of text positions.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, approx::sa_search_mismatches};
+///
+/// let input_string = b"GATTACA";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let matches = sa_search_mismatches(input_string, b"TAGA", &suffix_array, 1);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!((matches[0].position, matches[0].distance), (3, 1));
+/// ```
+pub fn sa_search_mismatches<T: Ord, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+    max_mismatches: usize,
+) -> Vec<ApproximateMatch> {
+    let mut matches = Vec::new();
+    let mut alignment = Vec::with_capacity(pattern.len());
+    search_mismatches(
+        input_string,
+        pattern,
+        suffix_array,
+        0..suffix_array.len(),
+        max_mismatches,
+        &mut alignment,
+        &mut matches,
+    );
+    matches.sort_unstable_by_key(|m| m.position);
+    matches
+}
+
+fn search_mismatches<T: Ord, S: SuffixArray + ?Sized>(
+    text: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+    ranks: Range<usize>,
+    budget: usize,
+    alignment: &mut Vec<EditOp>,
+    matches: &mut Vec<ApproximateMatch>,
+) {
+    let depth = alignment.len();
+    if depth == pattern.len() {
+        let distance = alignment.iter().filter(|&&op| op != EditOp::Match).count();
+        for position in Positions::new(suffix_array, ranks) {
+            matches.push(ApproximateMatch { position, length: depth, distance, alignment: alignment.clone() });
+        }
+        return;
+    }
+    if budget == 0 {
+        // Only the exact continuation is left
+        let ranks = narrow(text, suffix_array, ranks, depth, &pattern[depth]);
+        if !ranks.is_empty() {
+            alignment.push(EditOp::Match);
+            search_mismatches(text, pattern, suffix_array, ranks, 0, alignment, matches);
+            alignment.pop();
+        }
+        return;
+    }
+    for (symbol, child) in Children::new(text, suffix_array, ranks, depth) {
+        if *symbol == pattern[depth] {
+            alignment.push(EditOp::Match);
+            search_mismatches(text, pattern, suffix_array, child, budget, alignment, matches);
+        } else {
+            alignment.push(EditOp::Mismatch);
+            search_mismatches(text, pattern, suffix_array, child, budget - 1, alignment, matches);
+        }
+        alignment.pop();
+    }
+}
+
+/// Search for the pattern P in the string T with edit distance at most `max_distance`.  
+/// Input: Vector of symbols & vector of pattern string, its suffix array and the maximum edit distance.  
+/// Output: For each text position where a non-empty occurrence starts, the occurrence with the smallest distance  
+/// (the shortest one among ties), in order of text positions.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, approx::{sa_search_edits, EditOp}};
+///
+/// let input_string = b"GATTACA";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let matches = sa_search_edits(input_string, b"GTTA", &suffix_array, 1);
+/// assert_eq!((matches[0].position, matches[0].length, matches[0].distance), (0, 5, 1));
+/// assert_eq!(matches[0].alignment[1], EditOp::Insertion);
+/// ```
+pub fn sa_search_edits<T: Ord, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+    max_distance: usize,
+) -> Vec<ApproximateMatch> {
+    let mut search = EditSearch {
+        text: input_string,
+        pattern,
+        suffix_array,
+        max_distance,
+        rows: Vec::new(),
+        path: Vec::new(),
+        matches: Vec::new(),
+    };
+    search.rows.push((0..=pattern.len()).collect());
+    search.visit(0..suffix_array.len());
+
+    let mut matches = search.matches;
+    matches.sort_unstable_by_key(|m| (m.position, m.distance, m.length));
+    matches.dedup_by_key(|m| m.position);
+    matches
+}
+
+struct EditSearch<'a, T, S: ?Sized> {
+    text: &'a [T],
+    pattern: &'a [T],
+    suffix_array: &'a S,
+    max_distance: usize,
+    /// `rows[j][i]` is the edit distance between `pattern[..i]` and `path[..j]`.
+    rows: Vec<Vec<usize>>,
+    /// Symbols shared by the suffixes of the current range.
+    path: Vec<&'a T>,
+    matches: Vec<ApproximateMatch>,
+}
+
+impl<'a, T: Ord, S: SuffixArray + ?Sized> EditSearch<'a, T, S> {
+    fn visit(&mut self, ranks: Range<usize>) {
+        let depth = self.path.len();
+        let m = self.pattern.len();
+        let distance = self.rows[depth][m];
+        if depth > 0 && distance <= self.max_distance {
+            let alignment = self.traceback();
+            for position in Positions::new(self.suffix_array, ranks.clone()) {
+                self.matches.push(ApproximateMatch { position, length: depth, distance, alignment: alignment.clone() });
+            }
+        }
+        let (text, suffix_array) = (self.text, self.suffix_array);
+        for (symbol, child) in Children::new(text, suffix_array, ranks, depth) {
+            let row = &self.rows[depth];
+            let mut next = Vec::with_capacity(m + 1);
+            next.push(depth + 1);
+            for i in 1..=m {
+                let substitution = row[i - 1] + (self.pattern[i - 1] != *symbol) as usize;
+                next.push(substitution.min(row[i] + 1).min(next[i - 1] + 1));
+            }
+            // Distances never decrease along a branch
+            if next.iter().all(|&d| d > self.max_distance) {
+                continue;
+            }
+            self.rows.truncate(depth + 1);
+            self.rows.push(next);
+            self.path.push(symbol);
+            self.visit(child);
+            self.path.pop();
+        }
+    }
+
+    fn traceback(&self) -> Vec<EditOp> {
+        let (mut i, mut j) = (self.pattern.len(), self.path.len());
+        let mut alignment = Vec::with_capacity(i + j);
+        while i > 0 || j > 0 {
+            let d = self.rows[j][i];
+            if i > 0 && j > 0 {
+                let is_match = self.pattern[i - 1] == *self.path[j - 1];
+                if d == self.rows[j - 1][i - 1] + (!is_match) as usize {
+                    alignment.push(if is_match { EditOp::Match } else { EditOp::Mismatch });
+                    i -= 1;
+                    j -= 1;
+                    continue;
+                }
+            }
+            if j > 0 && d == self.rows[j - 1][i] + 1 {
+                alignment.push(EditOp::Insertion);
+                j -= 1;
+            } else {
+                alignment.push(EditOp::Deletion);
+                i -= 1;
+            }
+        }
+        alignment.reverse();
+        alignment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+    const PATTERNS: [&[u8]; 6] = [b"TACACCTG", b"CGGAAAC", b"GGGG", b"ATTTAC", b"AC", b"TTTTTTTT"];
+
+    fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0..=a.len()).collect();
+        for &c in b {
+            let mut next = vec![row[0] + 1];
+            for i in 1..=a.len() {
+                next.push((row[i - 1] + (a[i - 1] != c) as usize).min(row[i] + 1).min(next[i - 1] + 1));
+            }
+            row = next;
+        }
+        row[a.len()]
+    }
+
+    /// Applies the alignment and checks that it transforms `pattern` into `matched` with `distance` edits.
+    fn check_alignment(pattern: &[u8], matched: &[u8], m: &ApproximateMatch) {
+        let (mut i, mut j, mut distance) = (0, 0, 0);
+        for &op in &m.alignment {
+            match op {
+                EditOp::Match | EditOp::Mismatch => {
+                    assert_eq!(pattern[i] == matched[j], op == EditOp::Match);
+                    i += 1;
+                    j += 1;
+                }
+                EditOp::Insertion => j += 1,
+                EditOp::Deletion => i += 1,
+            }
+            distance += (op != EditOp::Match) as usize;
+        }
+        assert_eq!((i, j, distance), (pattern.len(), matched.len(), m.distance));
+    }
+
+    #[test]
+    fn test_sa_search_mismatches() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        for pattern in PATTERNS {
+            for k in 0..3 {
+                let matches = sa_search_mismatches(INPUT, pattern, &suffix_array, k);
+                let expected: Vec<(usize, usize)> = INPUT
+                    .windows(pattern.len())
+                    .enumerate()
+                    .map(|(p, window)| (p, window.iter().zip(pattern).filter(|(a, b)| a != b).count()))
+                    .filter(|&(_, d)| d <= k)
+                    .collect();
+                let found: Vec<(usize, usize)> = matches.iter().map(|m| (m.position, m.distance)).collect();
+                assert_eq!(found, expected);
+                for m in &matches {
+                    check_alignment(pattern, &INPUT[m.position..m.position + m.length], m);
+                }
+                assert_eq!(sa_search_mismatches(INPUT, pattern, &suffix_array_64, k), matches);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sa_search_edits() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        for pattern in PATTERNS {
+            for k in 0..3 {
+                let matches = sa_search_edits(INPUT, pattern, &suffix_array, k);
+                let expected: Vec<(usize, usize)> = (0..INPUT.len())
+                    .filter_map(|p| {
+                        let end = (p + pattern.len() + k).min(INPUT.len());
+                        let best = (p + 1..=end).map(|e| edit_distance(pattern, &INPUT[p..e])).min()?;
+                        Some((p, best)).filter(|&(_, d)| d <= k)
+                    })
+                    .collect();
+                let found: Vec<(usize, usize)> = matches.iter().map(|m| (m.position, m.distance)).collect();
+                assert_eq!(found, expected);
+                for m in &matches {
+                    check_alignment(pattern, &INPUT[m.position..m.position + m.length], m);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Searching many patterns against one text and suffix array.
+//!
+//! Results are the ranges of suffix array indices of [sa_search_range](crate::search::sa_search_range),
+//! in the order of the input patterns. An absent pattern gets an empty range at its insertion point.
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::sa::SuffixArray;
+use crate::search::{equal_range, narrow};
+
+/// Searches patterns in lexicographic order, reusing the ranges of the prefix shared with the previous pattern.
+fn search_in_order<T: Ord, P: AsRef<[T]>, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    patterns: &[P],
+    suffix_array: &S,
+    order: &[usize],
+    results: &mut Vec<(usize, Range<usize>)>,
+) {
+    // ranges[d] is the range of the first `d` symbols of the previous pattern
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    ranges.push(0..suffix_array.len());
+    let mut previous: &[T] = &[];
+    for &index in order {
+        let pattern = patterns[index].as_ref();
+        let shared = previous.iter().zip(pattern).take_while(|(a, b)| a == b).count();
+        ranges.truncate(shared + 1);
+        for depth in shared..pattern.len() {
+            let range = narrow(input_string, suffix_array, ranges[depth].clone(), depth, &pattern[depth]);
+            ranges.push(range);
+        }
+        results.push((index, ranges[pattern.len()].clone()));
+        previous = pattern;
+    }
+}
+
+fn sorted_order<T: Ord, P: AsRef<[T]>>(patterns: &[P]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..patterns.len()).collect();
+    order.sort_unstable_by(|&a, &b| patterns[a].as_ref().cmp(patterns[b].as_ref()));
+    order
+}
+
+fn scatter(count: usize, results: Vec<(usize, Range<usize>)>) -> Vec<Range<usize>> {
+    let mut ranges = vec![0..0; count];
+    for (index, range) in results {
+        ranges[index] = range;
+    }
+    ranges
+}
+
+/// Search for many patterns in the string T.  
+/// Input: Vector of symbols & patterns, its suffix array and whether to sort the patterns.  
+/// With `sort_patterns`, patterns are searched in lexicographic order  
+/// so that the search of a prefix shared with the previous pattern is not repeated.  
+/// Output: Ranges of suffix array indices of each pattern, in the order of the patterns.
+pub fn sa_search_batch<T: Ord, P: AsRef<[T]>, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    patterns: &[P],
+    suffix_array: &S,
+    sort_patterns: bool,
+) -> Vec<Range<usize>> {
+    if !sort_patterns {
+        return patterns
+            .iter()
+            .map(|pattern| {
+                let (lo, hi) = equal_range(input_string, pattern.as_ref(), suffix_array);
+                lo..hi
+            })
+            .collect();
+    }
+    let order = sorted_order(patterns);
+    let mut results = Vec::with_capacity(patterns.len());
+    search_in_order(input_string, patterns, suffix_array, &order, &mut results);
+    scatter(patterns.len(), results)
+}
+
+/// Parallel version of [sa_search_batch] using the global thread pool of `rayon`.  
+/// Sorted patterns are split into chunks of consecutive patterns, one search per chunk.
+#[cfg(feature = "parallel")]
+pub fn par_sa_search_batch<T, P, S>(
+    input_string: &[T],
+    patterns: &[P],
+    suffix_array: &S,
+    sort_patterns: bool,
+) -> Vec<Range<usize>>
+where
+    T: Ord + Sync,
+    P: AsRef<[T]> + Sync,
+    S: SuffixArray + Sync + ?Sized,
+{
+    use rayon::prelude::*;
+
+    if !sort_patterns {
+        return patterns
+            .par_iter()
+            .map(|pattern| {
+                let (lo, hi) = equal_range(input_string, pattern.as_ref(), suffix_array);
+                lo..hi
+            })
+            .collect();
+    }
+    let order = sorted_order(patterns);
+    let chunk_size = (patterns.len() / (rayon::current_num_threads() * 4)).max(1024);
+    let results = order
+        .par_chunks(chunk_size)
+        .map(|chunk| {
+            let mut results = Vec::with_capacity(chunk.len());
+            search_in_order(input_string, patterns, suffix_array, chunk, &mut results);
+            results
+        })
+        .reduce(Vec::new, |mut a, mut b| {
+            a.append(&mut b);
+            a
+        });
+    scatter(patterns.len(), results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::sa_search_range;
+    use crate::test_util::Lcg;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    fn patterns() -> Vec<Vec<u8>> {
+        let mut rng = Lcg::new(11);
+        (0..3000)
+            .map(|_| {
+                let seed = rng.next_u64();
+                let start = (seed >> 33) as usize % INPUT.len();
+                let length = (seed >> 20) as usize % 8;
+                let mut pattern = INPUT[start..(start + length).min(INPUT.len())].to_vec();
+                if seed & 1 == 1 {
+                    pattern.push(b"ACGT"[(seed >> 8) as usize % 4]);
+                }
+                pattern
+            })
+            .collect()
+    }
+
+    fn expected(patterns: &[Vec<u8>], suffix_array: &[i32]) -> Vec<Range<usize>> {
+        patterns
+            .iter()
+            .map(|pattern| match sa_search_range(INPUT, pattern, suffix_array) {
+                Ok(range) => range,
+                Err(rank) => rank..rank,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sa_search_batch() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let patterns = patterns();
+        let expected = expected(&patterns, &suffix_array);
+        assert_eq!(sa_search_batch(INPUT, &patterns, &suffix_array, false), expected);
+        assert_eq!(sa_search_batch(INPUT, &patterns, &suffix_array, true), expected);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_par_sa_search_batch() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let patterns = patterns();
+        let expected = expected(&patterns, &suffix_array);
+        assert_eq!(par_sa_search_batch(INPUT, &patterns, &suffix_array, false), expected);
+        assert_eq!(par_sa_search_batch(INPUT, &patterns, &suffix_array, true), expected);
+    }
+}
@@ -0,0 +1,88 @@
+//! Rust version of `examples/bwt.c` in `libdivsufsort`.
+//!
+//! Burrows-Wheeler transforms INFILE block by block and writes OUTFILE as:
+//! - the block size (32-bit little-endian)
+//! - for each block, its primary index (32-bit little-endian) followed by the transformed bytes
+
+mod common;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+use std::time::Instant;
+
+use common::{create_output, fail, read_block};
+
+const DEFAULT_BLOCK_SIZE_MIB: usize = 32;
+
+fn print_help(program_name: &str, status: i32) -> ! {
+    eprintln!("bwt, a burrows-wheeler transform program, using libdivsufsort {}", libdivsufsort_rs::divsufsort_version());
+    eprintln!("usage: {} [-b num] INFILE OUTFILE\n", program_name);
+    eprintln!("  -b num    set block size to num MiB [1..512] (default: {})", DEFAULT_BLOCK_SIZE_MIB);
+    eprintln!("  INFILE    input filename (\"-\" for stdin)");
+    eprintln!("  OUTFILE   output filename (\"-\" for stdout)");
+    process::exit(status);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program_name = args[0].as_str();
+    let mut block_size = DEFAULT_BLOCK_SIZE_MIB << 20;
+    let mut rest = &args[1..];
+    if rest.len() == 1 && (rest[0] == "-h" || rest[0] == "--help") {
+        print_help(program_name, 0);
+    }
+    if rest.len() >= 2 && rest[0] == "-b" {
+        match rest[1].parse::<usize>() {
+            Ok(mib) if (1..=512).contains(&mib) => block_size = mib << 20,
+            _ => print_help(program_name, 1),
+        }
+        rest = &rest[2..];
+    }
+    if rest.len() != 2 {
+        print_help(program_name, 1);
+    }
+    let (input_path, output_path) = (&rest[0], &rest[1]);
+
+    let mut reader: Box<dyn Read> = if input_path == "-" {
+        Box::new(io::stdin())
+    } else {
+        match File::open(input_path) {
+            Ok(file) => {
+                // Shrink the block for small files, to 0 for an empty file as upstream
+                if let Ok(metadata) = file.metadata() {
+                    block_size = block_size.min(metadata.len() as usize);
+                }
+                Box::new(file)
+            }
+            Err(err) => fail(program_name, format!("Cannot open file `{}': {}", input_path, err)),
+        }
+    };
+    let mut writer = create_output(program_name, output_path);
+
+    let write_error = |err: io::Error| fail(program_name, format!("Cannot write to `{}': {}", output_path, err));
+    writer.write_all(&(block_size as i32).to_le_bytes()).unwrap_or_else(write_error);
+
+    eprint!("  BWT (blocksize {}) ... ", block_size);
+    let start = Instant::now();
+    let mut block = vec![0u8; block_size];
+    let mut total = 0;
+    // No blocks follow a block size of 0
+    while !block.is_empty() {
+        let n = read_block(&mut reader, &mut block)
+            .unwrap_or_else(|err| fail(program_name, format!("Cannot read from `{}': {}", input_path, err)));
+        if n == 0 {
+            break;
+        }
+        let primary_index = match libdivsufsort_rs::divbwt(&mut block[..n]) {
+            Some(primary_index) => primary_index,
+            None => fail(program_name, "Cannot transform the block.".to_string()),
+        };
+        writer.write_all(&primary_index.to_le_bytes()).unwrap_or_else(write_error);
+        writer.write_all(&block[..n]).unwrap_or_else(write_error);
+        total += n;
+    }
+    writer.flush().unwrap_or_else(write_error);
+    eprintln!("{} bytes, {:.4} sec", total, start.elapsed().as_secs_f64());
+}
@@ -0,0 +1,66 @@
+//! Helpers shared by the command-line tools.
+
+// Each tool uses only some of the helpers
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+
+pub fn fail(program_name: &str, message: String) -> ! {
+    eprintln!("{}: {}", program_name, message);
+    process::exit(1);
+}
+
+/// Reads until `buffer` is full or the input ends.
+pub fn read_block(reader: &mut dyn Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// Reads a 32-bit little-endian integer, or `None` at the end of the input.
+pub fn read_int(reader: &mut dyn Read) -> io::Result<Option<i32>> {
+    let mut bytes = [0u8; 4];
+    match read_block(reader, &mut bytes)? {
+        0 => Ok(None),
+        4 => Ok(Some(i32::from_le_bytes(bytes))),
+        _ => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated integer")),
+    }
+}
+
+/// Reads a whole file (`-` for stdin) that fits a 32-bit suffix array, or exits.
+pub fn read_text(program_name: &str, input_path: &str) -> Vec<u8> {
+    let mut text = Vec::new();
+    let read_result = if input_path == "-" {
+        io::stdin().read_to_end(&mut text).map(|_| ())
+    } else {
+        File::open(input_path).and_then(|mut file| file.read_to_end(&mut text).map(|_| ()))
+    };
+    if let Err(err) = read_result {
+        fail(program_name, format!("Cannot read file `{}': {}", input_path, err));
+    }
+    if text.len() > i32::MAX as usize {
+        fail(program_name, format!("`{}' is too large ({} bytes)", input_path, text.len()));
+    }
+    text
+}
+
+/// Opens a buffered output file (`-` for stdout), or exits.
+pub fn create_output(program_name: &str, output_path: &str) -> Box<dyn Write> {
+    if output_path == "-" {
+        Box::new(BufWriter::new(io::stdout()))
+    } else {
+        match File::create(output_path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(err) => fail(program_name, format!("Cannot open file `{}': {}", output_path, err)),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Rust version of `examples/mksary.c` in `libdivsufsort`.
+//!
+//! Constructs the suffix array of INFILE and writes it to OUTFILE
+//! as native-endian 32-bit integers (`saidx_t`).
+
+mod common;
+
+use std::env;
+use std::io::Write;
+use std::process;
+use std::time::Instant;
+
+use common::{create_output, fail, read_text};
+
+fn print_help(program_name: &str, status: i32) -> ! {
+    eprintln!("mksary, a simple suffix array builder, using libdivsufsort {}", libdivsufsort_rs::divsufsort_version());
+    eprintln!("usage: {} INFILE OUTFILE\n", program_name);
+    eprintln!("  INFILE   input filename (\"-\" for stdin)");
+    eprintln!("  OUTFILE  output filename (\"-\" for stdout)");
+    process::exit(status);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program_name = args[0].as_str();
+    if args.len() == 2 && (args[1] == "-h" || args[1] == "--help") {
+        print_help(program_name, 0);
+    }
+    if args.len() != 3 {
+        print_help(program_name, 1);
+    }
+    let (input_path, output_path) = (&args[1], &args[2]);
+
+    let text = read_text(program_name, input_path);
+
+    // Construct the suffix array
+    eprint!("{}: {} bytes ... ", input_path, text.len());
+    let start = Instant::now();
+    let suffix_array = match libdivsufsort_rs::divsufsort(&text) {
+        Some(suffix_array) => suffix_array,
+        None => fail(program_name, "Cannot allocate memory.".to_string()),
+    };
+    eprintln!("{:.4} sec", start.elapsed().as_secs_f64());
+
+    // Write the suffix array through the buffer, without a second copy of it in bytes
+    let mut writer = create_output(program_name, output_path);
+    let write_result = suffix_array.iter().try_for_each(|value| writer.write_all(&value.to_ne_bytes()));
+    if let Err(err) = write_result.and_then(|_| writer.flush()) {
+        fail(program_name, format!("Cannot write to `{}': {}", output_path, err));
+    }
+}
@@ -0,0 +1,55 @@
+//! Rust version of `examples/sasearch.c` in `libdivsufsort`.
+//!
+//! Searches PATTERN in FILE using the suffix array in SAFILE (as written by `mksary`)
+//! and prints the position of each occurrence, one per line.
+
+mod common;
+
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use common::fail;
+
+fn print_help(program_name: &str, status: i32) -> ! {
+    eprintln!("sasearch, a simple SA-based full-text search tool, using libdivsufsort {}", libdivsufsort_rs::divsufsort_version());
+    eprintln!("usage: {} PATTERN FILE SAFILE", program_name);
+    process::exit(status);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program_name = args[0].as_str();
+    if args.len() == 2 && (args[1] == "-h" || args[1] == "--help") {
+        print_help(program_name, 0);
+    }
+    if args.len() != 4 {
+        print_help(program_name, 1);
+    }
+    let (pattern, text_path, sa_path) = (args[1].as_bytes(), &args[2], &args[3]);
+
+    let text = fs::read(text_path)
+        .unwrap_or_else(|err| fail(program_name, format!("Cannot read file `{}': {}", text_path, err)));
+    let sa_bytes = fs::read(sa_path)
+        .unwrap_or_else(|err| fail(program_name, format!("Cannot read file `{}': {}", sa_path, err)));
+    if sa_bytes.len() != text.len() * 4 {
+        fail(program_name, format!("`{}' is not a suffix array of `{}'", sa_path, text_path));
+    }
+    let suffix_array: Vec<i32> = sa_bytes
+        .chunks_exact(4)
+        .map(|chunk| i32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+
+    let (left, size) = match libdivsufsort_rs::sa_search(&text, pattern, &suffix_array) {
+        Some(result) => result,
+        None => fail(program_name, "Cannot search the pattern.".to_string()),
+    };
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    let write_error = |err: io::Error| fail(program_name, format!("Cannot write to stdout: {}", err));
+    for i in 0..size {
+        writeln!(writer, "{}", suffix_array[(left + i) as usize]).unwrap_or_else(write_error);
+    }
+    writer.flush().unwrap_or_else(write_error);
+}
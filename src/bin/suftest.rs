@@ -0,0 +1,46 @@
+//! Rust version of `examples/suftest.c` in `libdivsufsort`.
+//!
+//! Constructs the suffix array of FILE and checks its correctness with [sufcheck].
+//!
+//! [sufcheck]: libdivsufsort_rs::sufcheck
+
+mod common;
+
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use common::{fail, read_text};
+
+fn print_help(program_name: &str, status: i32) -> ! {
+    eprintln!("suftest, a suffixsort tester, using libdivsufsort {}", libdivsufsort_rs::divsufsort_version());
+    eprintln!("usage: {} FILE\n", program_name);
+    eprintln!("  FILE  input filename (\"-\" for stdin)");
+    process::exit(status);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program_name = args[0].as_str();
+    if args.len() == 2 && (args[1] == "-h" || args[1] == "--help") {
+        print_help(program_name, 0);
+    }
+    if args.len() != 2 {
+        print_help(program_name, 1);
+    }
+    let input_path = &args[1];
+
+    let text = read_text(program_name, input_path);
+
+    eprint!("{}: {} bytes ... ", input_path, text.len());
+    let start = Instant::now();
+    let suffix_array = match libdivsufsort_rs::divsufsort(&text) {
+        Some(suffix_array) => suffix_array,
+        None => fail(program_name, "Cannot allocate memory.".to_string()),
+    };
+    eprintln!("{:.4} sec", start.elapsed().as_secs_f64());
+
+    if libdivsufsort_rs::sufcheck(&text, &suffix_array, true).is_none() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,67 @@
+//! Rust version of `examples/unbwt.c` in `libdivsufsort`.
+//!
+//! Restores the original data from OUTFILE of the `bwt` tool.
+
+mod common;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+use std::time::Instant;
+
+use common::{create_output, fail, read_block, read_int};
+
+fn print_help(program_name: &str, status: i32) -> ! {
+    eprintln!("unbwt, an inverse burrows-wheeler transform program, using libdivsufsort {}", libdivsufsort_rs::divsufsort_version());
+    eprintln!("usage: {} INFILE OUTFILE\n", program_name);
+    eprintln!("  INFILE   input filename (\"-\" for stdin)");
+    eprintln!("  OUTFILE  output filename (\"-\" for stdout)");
+    process::exit(status);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program_name = args[0].as_str();
+    if args.len() == 2 && (args[1] == "-h" || args[1] == "--help") {
+        print_help(program_name, 0);
+    }
+    if args.len() != 3 {
+        print_help(program_name, 1);
+    }
+    let (input_path, output_path) = (&args[1], &args[2]);
+
+    let mut reader: Box<dyn Read> = if input_path == "-" {
+        Box::new(io::stdin())
+    } else {
+        match File::open(input_path) {
+            Ok(file) => Box::new(file),
+            Err(err) => fail(program_name, format!("Cannot open file `{}': {}", input_path, err)),
+        }
+    };
+    let mut writer = create_output(program_name, output_path);
+    let write_error = |err: io::Error| fail(program_name, format!("Cannot write to `{}': {}", output_path, err));
+
+    let read_error = |err: io::Error| -> ! { fail(program_name, format!("Cannot read from `{}': {}", input_path, err)) };
+
+    let block_size = match read_int(&mut reader).unwrap_or_else(|err| read_error(err)) {
+        Some(block_size) if block_size >= 0 => block_size as usize,
+        Some(_) => fail(program_name, format!("`{}' has an invalid block size", input_path)),
+        None => return,
+    };
+
+    eprint!("  UnBWT (blocksize {}) ... ", block_size);
+    let start = Instant::now();
+    let mut block = vec![0u8; block_size];
+    let mut total = 0;
+    while let Some(primary_index) = read_int(&mut reader).unwrap_or_else(|err| read_error(err)) {
+        let n = read_block(&mut reader, &mut block).unwrap_or_else(|err| read_error(err));
+        if libdivsufsort_rs::inverse_bw_transform(&mut block[..n], primary_index).is_none() {
+            fail(program_name, "Cannot restore the block.".to_string());
+        }
+        writer.write_all(&block[..n]).unwrap_or_else(write_error);
+        total += n;
+    }
+    writer.flush().unwrap_or_else(write_error);
+    eprintln!("{} bytes, {:.4} sec", total, start.elapsed().as_secs_f64());
+}
@@ -0,0 +1,81 @@
+//! Case-insensitive search over byte texts.
+//!
+//! The suffix array is constructed from an ASCII-lowercased copy of the text.
+//! Folding does not change the length, so positions are the same as in the original text.
+
+use alloc::vec::Vec;
+
+use crate::sa::SaIndex;
+use crate::search::{equal_range, Positions};
+
+/// Suffix array of the ASCII case-folded text.
+#[derive(Clone, Debug)]
+pub struct CaseInsensitiveIndex<I: SaIndex = i32> {
+    folded_string: Vec<u8>,
+    suffix_array: Vec<I>,
+}
+
+impl CaseInsensitiveIndex<i32> {
+    /// Constructs the index with [divsufsort](crate::divsufsort).
+    pub fn new(input_string: &[u8]) -> Option<Self> {
+        let folded_string = input_string.to_ascii_lowercase();
+        let suffix_array = crate::divsufsort(&folded_string)?;
+        Some(Self { folded_string, suffix_array })
+    }
+}
+
+impl CaseInsensitiveIndex<i64> {
+    /// 64-bit version of [new](CaseInsensitiveIndex::new)
+    pub fn new64(input_string: &[u8]) -> Option<Self> {
+        let folded_string = input_string.to_ascii_lowercase();
+        let suffix_array = crate::divsufsort64(&folded_string)?;
+        Some(Self { folded_string, suffix_array })
+    }
+}
+
+impl<I: SaIndex> CaseInsensitiveIndex<I> {
+    /// Search for the pattern P ignoring ASCII case.  
+    /// Output: Positions in the original text of the matches, in lexicographic order of the folded suffixes.  
+    /// The range of suffix array indices is given by [Positions::ranks].
+    pub fn search(&self, pattern: &[u8]) -> Positions<'_, Vec<I>> {
+        let folded_pattern = pattern.to_ascii_lowercase();
+        let (lo, hi) = equal_range(&self.folded_string, &folded_pattern, &self.suffix_array);
+        Positions::new(&self.suffix_array, lo..hi)
+    }
+    /// Number of occurrences of the pattern ignoring ASCII case.
+    pub fn count(&self, pattern: &[u8]) -> usize {
+        self.search(pattern).len()
+    }
+    /// ASCII-lowercased text the suffix array is constructed from.
+    pub fn folded_string(&self) -> &[u8] {
+        &self.folded_string
+    }
+    /// Suffix array of the [folded string](Self::folded_string).
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"2024-01-01 ERROR disk full\n2024-01-01 error: retry\n2024-01-02 Error Disk Full\n2024-01-03 warn\n\xC3\x89RROR";
+
+    #[test]
+    fn test_search() {
+        let index = CaseInsensitiveIndex::new(INPUT).unwrap();
+        let index_64 = CaseInsensitiveIndex::new64(INPUT).unwrap();
+        for pattern in [&b"error"[..], b"ERROR", b"Disk FULL", b"warn", b"\xC3\x89rror", b"fatal"] {
+            let mut positions: Vec<usize> = index.search(pattern).collect();
+            positions.sort_unstable();
+            let expected: Vec<usize> = (0..INPUT.len())
+                .filter(|&p| INPUT[p..].len() >= pattern.len() && INPUT[p..p + pattern.len()].eq_ignore_ascii_case(pattern))
+                .collect();
+            assert_eq!(positions, expected);
+            assert_eq!(index_64.count(pattern), expected.len());
+        }
+        // Non-ASCII bytes are not folded
+        assert_eq!(index.count(b"\xC3\xA9rror"), 0);
+    }
+}
@@ -0,0 +1,200 @@
+//! Suffix array checker written in Rust, reporting why a suffix array is not correct.
+//!
+//! Unlike [sufcheck](crate::sufcheck), nothing is printed and the failure is returned as [SufcheckError],
+//! for any [SuffixArray] (`i32`, `i64`, `u32` and [PackedSuffixArray](crate::packed::PackedSuffixArray)).
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::sa::SuffixArray;
+
+/// The first check `sufcheck` failed on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SufcheckError {
+    /// The lengths of the text and the suffix array are different.
+    LengthMismatch { text_length: usize, suffix_array_length: usize },
+    /// `SA[index]` is not in `[0, n-1]`.
+    OutOfRange { index: usize, position: i64 },
+    /// `SA[index]` is the same position as `SA[first_index]`.
+    Duplicate { first_index: usize, index: usize, position: usize },
+    /// Suffixes are not sorted by their first characters:
+    /// `T[SA[index - 1]] > T[SA[index]]`.
+    WrongOrder { index: usize, positions: (usize, usize), characters: (u8, u8) },
+    /// Suffixes starting with the same `character` are not ordered as the suffixes following it:
+    /// `SA[index]` should be `expected`, the suffix preceding the suffix at `expected + 1`.
+    WrongPosition { index: usize, expected: usize, found: usize, character: u8 },
+}
+
+impl fmt::Display for SufcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::LengthMismatch { text_length, suffix_array_length } => write!(
+                f,
+                "Lengths are different. |T|={} |SA|={}",
+                text_length, suffix_array_length,
+            ),
+            Self::OutOfRange { index, position } => write!(f, "Out of the range. SA[{}]={}", index, position),
+            Self::Duplicate { first_index, index, position } => write!(
+                f,
+                "Duplicated positions. SA[{}]=SA[{}]={}",
+                first_index, index, position,
+            ),
+            Self::WrongOrder { index, positions, characters } => write!(
+                f,
+                "Suffixes in wrong order. T[SA[{}]={}]={} > T[SA[{}]={}]={}",
+                index - 1, positions.0, characters.0, index, positions.1, characters.1,
+            ),
+            Self::WrongPosition { index, expected, found, character } => write!(
+                f,
+                "Suffix in wrong position. SA[{}]={} (expected {}, starting with {})",
+                index, found, expected, character,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SufcheckError {}
+
+/// Checks the correctness of `suffix_array` in `O(n)` time like `sufcheck` of `libdivsufsort`:
+/// entries are a permutation of the text positions, suffixes are sorted by their first characters,
+/// and suffixes sharing a first character are ordered as the suffixes following it.
+pub fn verify<S: SuffixArray + ?Sized>(input_string: &[u8], suffix_array: &S) -> Result<(), SufcheckError> {
+    let n = input_string.len();
+    if suffix_array.len() != n {
+        return Err(SufcheckError::LengthMismatch {
+            text_length: n,
+            suffix_array_length: suffix_array.len(),
+        });
+    }
+    if n == 0 {
+        return Ok(());
+    }
+
+    // Range and duplicates
+    let mut seen = vec![0u64; (n + 63) / 64];
+    for index in 0..n {
+        let position = suffix_array.position(index);
+        if position >= n {
+            // Negative entries of signed types are sign-extended
+            return Err(SufcheckError::OutOfRange { index, position: position as i64 });
+        }
+        if seen[position / 64] >> (position % 64) & 1 == 1 {
+            let first_index = (0..index).find(|&i| suffix_array.position(i) == position).unwrap();
+            return Err(SufcheckError::Duplicate { first_index, index, position });
+        }
+        seen[position / 64] |= 1 << (position % 64);
+    }
+    drop(seen);
+
+    // First characters
+    let mut bucket_starts = [0usize; 257];
+    for index in 0..n {
+        let c = input_string[suffix_array.position(index)];
+        if index > 0 {
+            let previous = suffix_array.position(index - 1);
+            if input_string[previous] > c {
+                return Err(SufcheckError::WrongOrder {
+                    index,
+                    positions: (previous, suffix_array.position(index)),
+                    characters: (input_string[previous], c),
+                });
+            }
+        }
+        bucket_starts[c as usize + 1] += 1;
+    }
+    for c in 0..256 {
+        bucket_starts[c + 1] += bucket_starts[c];
+    }
+
+    // Induced order: visiting suffixes in order, their predecessors fill each bucket in order
+    let mut next: Vec<usize> = bucket_starts[..256].to_vec();
+    let followers = core::iter::once(n).chain((0..n).map(|index| suffix_array.position(index)));
+    for follower in followers {
+        if follower == 0 {
+            continue;
+        }
+        let c = input_string[follower - 1];
+        let index = next[c as usize];
+        let found = suffix_array.position(index);
+        if found != follower - 1 {
+            return Err(SufcheckError::WrongPosition { index, expected: follower - 1, found, character: c });
+        }
+        next[c as usize] += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packed::PackedSuffixArray;
+    use crate::test_util::Lcg;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    #[test]
+    fn test_verify_against_naive_comparison() {
+        let mut rng = Lcg::new(3);
+        let mut random = move |bound: usize| rng.below(bound);
+        for length in 1..120 {
+            let input_string: Vec<u8> = (0..length).map(|_| b"ACGT"[random(4)]).collect();
+            let mut suffix_array = crate::divsufsort(&input_string).unwrap();
+            assert_eq!(verify(&input_string, &suffix_array), Ok(()));
+
+            let (a, b) = (random(length), random(length));
+            suffix_array.swap(a, b);
+            let is_sorted = suffix_array.windows(2).all(|w| input_string[w[0] as usize..] < input_string[w[1] as usize..]);
+            assert_eq!(verify(&input_string, &suffix_array).is_ok(), is_sorted);
+        }
+    }
+
+    #[test]
+    fn test_verify_reports() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        let suffix_array_u32 = crate::divsufsort_u32(INPUT).unwrap();
+        let packed = PackedSuffixArray::new_compact(suffix_array_64.clone());
+        assert_eq!(verify(INPUT, &suffix_array), Ok(()));
+        assert_eq!(verify(INPUT, &suffix_array_64), Ok(()));
+        assert_eq!(verify(INPUT, &suffix_array_u32), Ok(()));
+        assert_eq!(verify(INPUT, &packed), Ok(()));
+
+        assert_eq!(
+            verify(INPUT, &suffix_array[1..]),
+            Err(SufcheckError::LengthMismatch { text_length: INPUT.len(), suffix_array_length: INPUT.len() - 1 }),
+        );
+
+        let mut corrupted = suffix_array.clone();
+        corrupted[3] = -1;
+        assert_eq!(verify(INPUT, &corrupted), Err(SufcheckError::OutOfRange { index: 3, position: -1 }));
+
+        let mut corrupted = suffix_array_64.clone();
+        corrupted[5] = corrupted[2];
+        let position = corrupted[2] as usize;
+        assert_eq!(verify(INPUT, &corrupted), Err(SufcheckError::Duplicate { first_index: 2, index: 5, position }));
+
+        // The last `A` suffix and the first `C` suffix
+        let mut corrupted = suffix_array_u32.clone();
+        let (_, a_count) = crate::sa_simplesearch_u32(INPUT, &suffix_array_u32, 'A' as i32).unwrap();
+        let a_count = a_count as usize;
+        corrupted.swap(a_count - 1, a_count);
+        let error = verify(INPUT, &corrupted).unwrap_err();
+        assert_eq!(
+            error,
+            SufcheckError::WrongOrder {
+                index: a_count,
+                positions: (suffix_array_u32[a_count] as usize, suffix_array_u32[a_count - 1] as usize),
+                characters: (b'C', b'A'),
+            }
+        );
+
+        // Two suffixes starting with `A`
+        let mut corrupted = suffix_array_u32;
+        corrupted.swap(0, 1);
+        match verify(INPUT, &corrupted) {
+            Err(SufcheckError::WrongPosition { character, .. }) => assert_eq!(character, b'A'),
+            other => panic!("{:?}", other),
+        }
+    }
+}
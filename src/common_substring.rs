@@ -0,0 +1,275 @@
+//! Common substrings of two texts with a generalized suffix array.
+//!
+//! The texts are concatenated with a separator that occurs in neither, so no common prefix of two suffixes
+//! crosses it. A substring occurs in both texts exactly when its lcp-interval contains suffixes of both.
+//! If all 256 byte values occur, the concatenation is sorted as `u16` symbols by [integer](crate::integer).
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::esa::EnhancedSuffixArray;
+
+/// A substring occurring in both texts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommonSubstring {
+    /// Length of the substring.
+    pub length: usize,
+    /// Sorted start positions in the first text.
+    pub positions_a: Vec<usize>,
+    /// Sorted start positions in the second text.
+    pub positions_b: Vec<usize>,
+}
+
+/// Suffix array, LCP array and child table of `a`, a separator and `b`.
+#[derive(Clone, Debug)]
+pub struct GeneralizedSuffixArray {
+    esa: EnhancedSuffixArray<i64>,
+    length_a: usize,
+    /// `left_differs[r]` for `r > 0`: the suffixes at ranks `r - 1` and `r` do not have the same previous symbol  
+    /// in the same text (a suffix at the start of a text has no previous symbol).
+    left_differs: Vec<bool>,
+}
+
+impl GeneralizedSuffixArray {
+    /// Constructs the generalized suffix array with [divsufsort64](crate::divsufsort64).
+    pub fn new(a: &[u8], b: &[u8]) -> Option<Self> {
+        let mut used = [false; 256];
+        for &c in a.iter().chain(b) {
+            used[c as usize] = true;
+        }
+        match used.iter().position(|&used| !used) {
+            Some(separator) => {
+                let mut text = Vec::with_capacity(a.len() + b.len() + 1);
+                text.extend_from_slice(a);
+                text.push(separator as u8);
+                text.extend_from_slice(b);
+                let suffix_array = crate::divsufsort64(&text)?;
+                Some(Self::from_text(&text, suffix_array, a.len()))
+            }
+            None => {
+                // Bytes are shifted by one to free 0 for the separator
+                let text: Vec<u16> =
+                    a.iter().map(|&c| c as u16 + 1).chain(Some(0)).chain(b.iter().map(|&c| c as u16 + 1)).collect();
+                let suffix_array = crate::integer::divsufsort64(&text)?;
+                Some(Self::from_text(&text, suffix_array, a.len()))
+            }
+        }
+    }
+
+    fn from_text<T: Eq>(text: &[T], suffix_array: Vec<i64>, length_a: usize) -> Self {
+        let lcp = crate::lcp::lcp_array(text, &suffix_array);
+        let previous = |position: i64| {
+            let position = position as usize;
+            if position == 0 || position == length_a + 1 {
+                None
+            } else {
+                Some(&text[position - 1])
+            }
+        };
+        let mut left_differs = vec![false; suffix_array.len()];
+        for (r, pair) in suffix_array.windows(2).enumerate() {
+            left_differs[r + 1] = match (previous(pair[0]), previous(pair[1])) {
+                (Some(x), Some(y)) => x != y,
+                _ => true,
+            };
+        }
+        Self { esa: EnhancedSuffixArray::from_parts(suffix_array, lcp), length_a, left_differs }
+    }
+
+    /// Length of the first text.
+    pub fn length_a(&self) -> usize {
+        self.length_a
+    }
+    /// Suffix array of the concatenation. Positions after `length_a` are in the second text.
+    pub fn suffix_array(&self) -> &[i64] {
+        self.esa.suffix_array()
+    }
+    /// LCP array of the concatenation. No common prefix crosses the separator.
+    pub fn lcp(&self) -> &[i64] {
+        self.esa.lcp()
+    }
+
+    fn is_in_a(&self, rank: usize) -> bool {
+        (self.suffix_array()[rank] as usize) < self.length_a
+    }
+    fn common_substring(&self, length: usize, ranks: Range<usize>) -> CommonSubstring {
+        let (mut positions_a, mut positions_b) = (Vec::new(), Vec::new());
+        for &position in &self.suffix_array()[ranks] {
+            let position = position as usize;
+            if position < self.length_a {
+                positions_a.push(position);
+            } else {
+                positions_b.push(position - self.length_a - 1);
+            }
+        }
+        positions_a.sort_unstable();
+        positions_b.sort_unstable();
+        CommonSubstring { length, positions_a, positions_b }
+    }
+
+    /// Finds a longest common substring in `O(n)`.  
+    /// Output: The lexicographically smallest of the longest common substrings with all of its occurrences,  
+    /// `None` if the texts have no symbol in common.
+    /// ```rust
+    /// use libdivsufsort_rs::common_substring::GeneralizedSuffixArray;
+    ///
+    /// let gsa = GeneralizedSuffixArray::new(b"xabcdey", b"zzbcdezz").unwrap();
+    /// let longest = gsa.longest().unwrap();
+    /// assert_eq!((longest.length, longest.positions_a, longest.positions_b), (4, vec![2], vec![2]));
+    /// ```
+    pub fn longest(&self) -> Option<CommonSubstring> {
+        let lcp = self.lcp();
+        let (mut length, mut rank) = (0, 0);
+        for (r, &common) in lcp.iter().enumerate().skip(1) {
+            if common as usize > length && self.is_in_a(r - 1) != self.is_in_a(r) {
+                length = common as usize;
+                rank = r;
+            }
+        }
+        if length == 0 {
+            return None;
+        }
+        let mut start = rank - 1;
+        while start > 0 && lcp[start] as usize >= length {
+            start -= 1;
+        }
+        let mut end = rank + 1;
+        while end < lcp.len() && lcp[end] as usize >= length {
+            end += 1;
+        }
+        Some(self.common_substring(length, start..end))
+    }
+
+    /// Finds the maximal common substrings of at least `min_length` symbols.  
+    /// A common substring is maximal if its occurrences in the texts are neither all followed by the same symbol  
+    /// nor all preceded by the same symbol, so every common substring of at least `min_length` symbols  
+    /// is a substring of a reported one.  
+    /// Output: Common substrings with their occurrences, the longest first.
+    /// ```rust
+    /// use libdivsufsort_rs::common_substring::GeneralizedSuffixArray;
+    ///
+    /// let gsa = GeneralizedSuffixArray::new(b"ACGTTACGA", b"TACGTT").unwrap();
+    /// let common = gsa.common_substrings(3);
+    /// let lengths: Vec<usize> = common.iter().map(|common| common.length).collect();
+    /// assert_eq!(lengths, [5, 4, 3]); // ACGTT, TACG, ACG
+    /// assert_eq!((&common[1].positions_a[..], &common[1].positions_b[..]), (&[4][..], &[0][..]));
+    /// ```
+    pub fn common_substrings(&self, min_length: usize) -> Vec<CommonSubstring> {
+        let min_length = min_length.max(1);
+        // Prefix sums to check intervals in `O(1)`
+        let mut count_a = vec![0usize; self.esa.len() + 1];
+        let mut count_left_differs = vec![0usize; self.esa.len() + 1];
+        for r in 0..self.esa.len() {
+            count_a[r + 1] = count_a[r] + self.is_in_a(r) as usize;
+            count_left_differs[r + 1] = count_left_differs[r] + self.left_differs[r] as usize;
+        }
+
+        let mut common: Vec<CommonSubstring> = self
+            .esa
+            .intervals_bottom_up()
+            .filter(|interval| {
+                let (start, end) = (interval.ranks.start, interval.ranks.end);
+                let in_a = count_a[end] - count_a[start];
+                interval.depth >= min_length
+                    && 0 < in_a
+                    && in_a < end - start
+                    && count_left_differs[end] - count_left_differs[start + 1] > 0
+            })
+            .map(|interval| self.common_substring(interval.depth, interval.ranks))
+            .collect();
+        common.sort_unstable_by(|x, y| y.length.cmp(&x.length).then_with(|| x.positions_a.cmp(&y.positions_a)));
+        common
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeSet;
+    use crate::test_util::Lcg;
+
+    fn random_dna(rng: &mut Lcg, length: usize) -> Vec<u8> {
+        (0..length).map(|_| b"ACGT"[(rng.next_u64() >> 62) as usize]).collect()
+    }
+
+    fn occurrences(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        (0..=text.len().saturating_sub(pattern.len())).filter(|&i| text[i..].starts_with(pattern)).collect()
+    }
+
+    /// Maximal common substrings by the definition, with the ends of the texts as distinct symbols.
+    fn naive_common_substrings(a: &[u8], b: &[u8], min_length: usize) -> BTreeSet<Vec<u8>> {
+        let mut result = BTreeSet::new();
+        for start in 0..a.len() {
+            for end in start + min_length..=a.len() {
+                let pattern = &a[start..end];
+                let (in_a, in_b) = (occurrences(a, pattern), occurrences(b, pattern));
+                if in_b.is_empty() {
+                    continue;
+                }
+                let contexts = |offset: isize| {
+                    let mut contexts = BTreeSet::new();
+                    for (text, positions) in [(a, &in_a), (b, &in_b)] {
+                        for &position in positions.iter() {
+                            let index = if offset < 0 { position as isize - 1 } else { (position + pattern.len()) as isize };
+                            match text.get(index as usize) {
+                                Some(&c) if index >= 0 => contexts.insert(c as i32),
+                                _ => contexts.insert(-1 - contexts.len() as i32),
+                            };
+                        }
+                    }
+                    contexts.len()
+                };
+                if contexts(-1) > 1 && contexts(1) > 1 {
+                    result.insert(pattern.to_vec());
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_common_substrings() {
+        let mut rng = Lcg::new(11);
+        for _ in 0..20 {
+            let a = random_dna(&mut rng, 40);
+            let b = random_dna(&mut rng, 30);
+            let gsa = GeneralizedSuffixArray::new(&a, &b).unwrap();
+            for min_length in [1, 3, 5] {
+                let common = gsa.common_substrings(min_length);
+                let mut strings = BTreeSet::new();
+                for substring in &common {
+                    let pattern = &a[substring.positions_a[0]..substring.positions_a[0] + substring.length];
+                    assert_eq!(substring.positions_a, occurrences(&a, pattern));
+                    assert_eq!(substring.positions_b, occurrences(&b, pattern));
+                    strings.insert(pattern.to_vec());
+                }
+                assert_eq!(strings.len(), common.len());
+                assert_eq!(strings, naive_common_substrings(&a, &b, min_length));
+            }
+
+            let longest = gsa.longest().unwrap();
+            let naive = (0..a.len())
+                .flat_map(|i| (0..b.len()).map(move |j| (i, j)))
+                .map(|(i, j)| a[i..].iter().zip(&b[j..]).take_while(|(x, y)| x == y).count())
+                .max()
+                .unwrap();
+            assert_eq!(longest.length, naive);
+            let pattern = &b[longest.positions_b[0]..longest.positions_b[0] + longest.length];
+            assert_eq!(longest.positions_a, occurrences(&a, pattern));
+        }
+    }
+
+    #[test]
+    fn test_all_bytes() {
+        let a: Vec<u8> = (0..=255).collect();
+        let b: Vec<u8> = (100..=200).rev().chain(50..120).collect();
+        let gsa = GeneralizedSuffixArray::new(&a, &b).unwrap();
+        let longest = gsa.longest().unwrap();
+        assert_eq!((longest.length, longest.positions_a, longest.positions_b), (70, vec![50], vec![101]));
+
+        let gsa = GeneralizedSuffixArray::new(b"AAA", b"CCC").unwrap();
+        assert_eq!(gsa.longest(), None);
+        assert!(gsa.common_substrings(1).is_empty());
+        assert_eq!(GeneralizedSuffixArray::new(b"", b"").unwrap().longest(), None);
+    }
+}
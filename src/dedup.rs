@@ -0,0 +1,262 @@
+//! Exact substring deduplication of a corpus (Lee et al. 2022, "Deduplicating Training Data Makes Language Models Better").
+//!
+//! The documents are joined by a separator byte that occurs in none of them and sorted with
+//! [divsufsort64](crate::divsufsort64). If all 256 byte values occur, the bytes are shifted by one to free 0
+//! as the separator, and the documents are sorted as `u16` symbols by [integer](crate::integer) instead.
+//! A substring of at least `L` bytes occurs twice exactly when the suffixes at both occurrences share
+//! a prefix of `L` bytes in their documents, which is found from the LCP array
+//! (or the longest previous factors to keep the first occurrence).
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+/// Documents joined by a separator, with their suffix array and LCP array.
+#[derive(Clone, Debug)]
+pub struct Deduplicator {
+    text: Vec<u8>,
+    suffix_array: Vec<i64>,
+    lcp: Vec<i64>,
+    /// Start of each document in `text`.
+    starts: Vec<usize>,
+    separator: Option<u8>,
+}
+
+impl Deduplicator {
+    /// Joins and sorts the documents.  
+    /// Output: `None` if the suffix array cannot be constructed.
+    pub fn new<D: AsRef<[u8]>>(documents: &[D]) -> Option<Self> {
+        let mut used = [false; 256];
+        for document in documents {
+            for &c in document.as_ref() {
+                used[c as usize] = true;
+            }
+        }
+        // 0xFF never occurs in UTF-8, so it is the separator for text corpora
+        let separator = (0..=255u8).rev().find(|&c| !used[c as usize]);
+
+        let total: usize = documents.iter().map(|document| document.as_ref().len() + 1).sum();
+        let mut text = Vec::with_capacity(total);
+        let mut starts = Vec::with_capacity(documents.len());
+        for (index, document) in documents.iter().enumerate() {
+            if index > 0 {
+                text.push(separator.unwrap_or(0));
+            }
+            starts.push(text.len());
+            text.extend_from_slice(document.as_ref());
+        }
+        let (suffix_array, lcp) = match separator {
+            Some(_) => {
+                let suffix_array = crate::divsufsort64(&text)?;
+                let lcp = crate::lcp::lcp_array(&text, &suffix_array);
+                (suffix_array, lcp)
+            }
+            None => {
+                // Bytes are shifted by one to free 0 for the separators
+                let mut symbols: Vec<u16> = text.iter().map(|&c| c as u16 + 1).collect();
+                for &start in &starts[1..] {
+                    symbols[start - 1] = 0;
+                }
+                let suffix_array = crate::integer::divsufsort64(&symbols)?;
+                let lcp = crate::lcp::lcp_array(&symbols, &suffix_array);
+                (suffix_array, lcp)
+            }
+        };
+        Some(Self { text, suffix_array, lcp, starts, separator })
+    }
+
+    /// Number of documents.
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+    /// Whether there are no documents.
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+    /// Bytes of a document.
+    pub fn document(&self, index: usize) -> &[u8] {
+        &self.text[self.document_range(index)]
+    }
+    /// Range of a document in [text](Self::text).
+    pub fn document_range(&self, index: usize) -> Range<usize> {
+        let end = self.starts.get(index + 1).map_or(self.text.len(), |&next| next - 1);
+        self.starts[index]..end
+    }
+    /// Documents joined by the separator, or by 0 if they were sorted as `u16` symbols.
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+    /// Byte between the documents in [text](Self::text), `None` if all byte values occur in the documents.
+    pub fn separator(&self) -> Option<u8> {
+        self.separator
+    }
+    /// Suffix array of [text](Self::text).  
+    /// If [separator](Self::separator) is `None`, it is sorted for the bytes shifted by one as `u16` symbols with 0 separators,  
+    /// which sort below every byte, so it cannot be searched with the 0 separators of `text`.
+    pub fn suffix_array(&self) -> &[i64] {
+        &self.suffix_array
+    }
+    /// LCP array of [text](Self::text), which may cross document boundaries.  
+    /// If [separator](Self::separator) is `None`, it belongs to the `u16` symbols of [suffix_array](Self::suffix_array).
+    pub fn lcp(&self) -> &[i64] {
+        &self.lcp
+    }
+
+    /// Finds the bytes covered by a substring of at least `min_length` bytes that occurs more than once,  
+    /// within a document or across documents.  
+    /// With `keep_first`, only bytes covered by a substring that also occurs earlier in the corpus are reported,  
+    /// so removing them keeps the first occurrence of every duplicate.  
+    /// Output: Sorted, merged ranges to remove for each document, relative to the document.
+    /// ```rust
+    /// use libdivsufsort_rs::dedup::Deduplicator;
+    ///
+    /// let documents = ["the cat sat on the mat", "a dog sat on the mat", "unique"];
+    /// let dedup = Deduplicator::new(&documents).unwrap();
+    /// // " sat on the mat" occurs in both of the first documents
+    /// assert_eq!(dedup.duplicate_ranges(10, false), vec![vec![7..22], vec![5..20], vec![]]);
+    /// assert_eq!(dedup.duplicate_ranges(10, true), vec![vec![], vec![5..20], vec![]]);
+    /// ```
+    pub fn duplicate_ranges(&self, min_length: usize, keep_first: bool) -> Vec<Vec<Range<usize>>> {
+        let min_length = min_length.max(1);
+        let n = self.text.len();
+        // Length of the duplicated prefix of each suffix, in text order and not counting the separator
+        let lengths: Vec<i64> = if keep_first {
+            crate::lz77::lpf_array(&self.suffix_array, &self.lcp).lengths
+        } else {
+            let mut lengths = vec![0; n];
+            for rank in 1..n {
+                let common = self.lcp[rank];
+                let (a, b) = (self.suffix_array[rank - 1] as usize, self.suffix_array[rank] as usize);
+                lengths[a] = lengths[a].max(common);
+                lengths[b] = lengths[b].max(common);
+            }
+            lengths
+        };
+
+        (0..self.len())
+            .map(|index| {
+                let document = self.document_range(index);
+                let mut ranges: Vec<Range<usize>> = Vec::new();
+                for position in document.clone() {
+                    let length = (lengths[position] as usize).min(document.end - position);
+                    if length < min_length {
+                        continue;
+                    }
+                    let range = position - document.start..position + length - document.start;
+                    match ranges.last_mut() {
+                        Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                        _ => ranges.push(range),
+                    }
+                }
+                ranges
+            })
+            .collect()
+    }
+
+    /// Parts of a document that are not in `ranges` (sorted and disjoint, as from [duplicate_ranges](Self::duplicate_ranges)).
+    pub fn retained<'a>(&'a self, index: usize, ranges: &'a [Range<usize>]) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let document = self.document(index);
+        let starts = core::iter::once(0).chain(ranges.iter().map(|range| range.end));
+        let ends = ranges.iter().map(|range| range.start).chain(core::iter::once(document.len()));
+        starts.zip(ends).map(move |(start, end)| &document[start..end]).filter(|part| !part.is_empty())
+    }
+
+    /// Writes the cleaned corpus: every document without its duplicate ranges, followed by `terminator`.
+    #[cfg(feature = "std")]
+    pub fn write_deduplicated<W: std::io::Write>(
+        &self,
+        ranges: &[Vec<Range<usize>>],
+        terminator: &[u8],
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        assert_eq!(ranges.len(), self.len(), "number of range lists and documents are different");
+        for (index, ranges) in ranges.iter().enumerate() {
+            for part in self.retained(index, ranges) {
+                writer.write_all(part)?;
+            }
+            writer.write_all(terminator)?;
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeSet;
+    use crate::test_util::Lcg;
+
+    fn random_documents(rng: &mut Lcg) -> Vec<Vec<u8>> {
+        (0..6).map(|_| (0..rng.below(40)).map(|_| b"ab"[rng.below(2)]).collect()).collect()
+    }
+
+    /// Bytes covered by a window of `min_length` bytes that occurs at another (or an earlier) position.
+    fn naive_ranges(documents: &[Vec<u8>], min_length: usize, keep_first: bool) -> Vec<BTreeSet<usize>> {
+        let windows: Vec<(usize, usize, &[u8])> = documents
+            .iter()
+            .enumerate()
+            .flat_map(|(index, document)| {
+                (0..(document.len() + 1).saturating_sub(min_length))
+                    .map(move |position| (index, position, &document[position..position + min_length]))
+            })
+            .collect();
+        let mut covered = vec![BTreeSet::new(); documents.len()];
+        for (i, &(index, position, window)) in windows.iter().enumerate() {
+            let other = if keep_first { &windows[..i] } else { &windows[..] };
+            if other.iter().enumerate().any(|(j, &(_, _, w))| j != i && w == window) {
+                covered[index].extend(position..position + min_length);
+            }
+        }
+        covered
+    }
+
+    #[test]
+    fn test_duplicate_ranges() {
+        let mut rng = Lcg::new(5);
+        for iteration in 0..30 {
+            let mut documents = random_documents(&mut rng);
+            if iteration % 2 == 1 {
+                // Sorted as `u16` symbols
+                documents.push((0..=255).collect());
+            }
+            let dedup = Deduplicator::new(&documents).unwrap();
+            assert_eq!(dedup.separator(), if iteration % 2 == 1 { None } else { Some(0xFF) });
+            for min_length in [1, 4, 9] {
+                for keep_first in [false, true] {
+                    let ranges = dedup.duplicate_ranges(min_length, keep_first);
+                    let covered: Vec<BTreeSet<usize>> =
+                        ranges.iter().map(|ranges| ranges.iter().cloned().flatten().collect()).collect();
+                    assert_eq!(covered, naive_ranges(&documents, min_length, keep_first));
+                    for ranges in &ranges {
+                        assert!(ranges.windows(2).all(|w| w[0].end < w[1].start));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_retained() {
+        let documents = [&b"xxHELLO WORLDyy"[..], b"HELLO WORLD", b"zz"];
+        let dedup = Deduplicator::new(&documents).unwrap();
+        assert_eq!(dedup.document(1), b"HELLO WORLD");
+        let ranges = dedup.duplicate_ranges(5, true);
+        assert_eq!(ranges, vec![vec![], vec![0..11], vec![]]);
+        let parts: Vec<&[u8]> = dedup.retained(0, &[0..2, 7..13]).collect();
+        assert_eq!(parts, [&b"HELLO"[..], b"yy"]);
+        assert!(Deduplicator::new::<&[u8]>(&[]).unwrap().duplicate_ranges(1, false).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_deduplicated() {
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let documents = [&b"xxHELLO WORLDyy"[..], b"HELLO WORLD", b"zz", &all_bytes];
+        let dedup = Deduplicator::new(&documents).unwrap();
+        let mut output = Vec::new();
+        dedup.write_deduplicated(&dedup.duplicate_ranges(5, false), b"\n", &mut output).unwrap();
+        let mut expected = b"xxyy\n\nzz\n".to_vec();
+        expected.extend_from_slice(&all_bytes);
+        expected.push(b'\n');
+        assert_eq!(output, expected);
+    }
+}
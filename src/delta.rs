@@ -0,0 +1,332 @@
+//! Binary diff and patch in the style of `bsdiff` (Percival 2003).
+//!
+//! The suffix array of the old buffer is searched for the longest matches of the new buffer, which are
+//! extended into approximate matches. A patch stores bytewise differences against the old buffer for the
+//! approximate matches and the new bytes between them.
+//!
+//! ## Patch format
+//! Patches are not compressed (the differences are mostly zeros, so compressing them is recommended).
+//! All integers are 8-byte little-endian.
+//!
+//! | Offset | Size | Content |
+//! |---|---|---|
+//! | 0 | 8 | Magic `DSSDIFF1` |
+//! | 8 | 8 | Length of the control block in bytes (`24 * number of controls`) |
+//! | 16 | 8 | Length of the diff block |
+//! | 24 | 8 | Length of the new buffer, equal to the lengths of the diff and the extra blocks |
+//! | 32 | | Control block, diff block and extra block |
+//!
+//! Each control is three integers `(add, copy, seek)`: the next `add` new bytes are the old bytes plus the next
+//! `add` bytes of the diff block (wrapping), the next `copy` new bytes are the next bytes of the extra block,
+//! and then the position in the old buffer moves by `seek` (signed, two's complement).
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::sa::SuffixArray;
+
+/// Magic bytes of a patch.
+pub const MAGIC: &[u8; 8] = b"DSSDIFF1";
+const HEADER_LENGTH: usize = 32;
+
+/// Longest common prefix of two byte strings.
+fn match_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Longest match of `new` in the suffixes of `old`, as (length, position).
+fn search<S: SuffixArray + ?Sized>(suffix_array: &S, old: &[u8], new: &[u8], mut lo: usize, mut hi: usize) -> (usize, usize) {
+    while hi - lo >= 2 {
+        let mid = lo + (hi - lo) / 2;
+        let position = suffix_array.position(mid);
+        let length = (old.len() - position).min(new.len());
+        if old[position..position + length] < new[..length] {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let (a, b) = (suffix_array.position(lo), suffix_array.position(hi));
+    let (x, y) = (match_length(&old[a..], new), match_length(&old[b..], new));
+    if x > y {
+        (x, a)
+    } else {
+        (y, b)
+    }
+}
+
+fn write_u64(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Creates a patch from `old` to `new`.  
+/// Output: Patch in the format of the module, `None` if the suffix array cannot be constructed.
+/// ```rust
+/// use libdivsufsort_rs::delta::{diff, patch};
+///
+/// let old = b"firmware v1.0: the quick brown fox jumps over the lazy dog".to_vec();
+/// let new = b"firmware v1.1: the quick brown fox jumped over the lazy dogs".to_vec();
+/// let delta = diff(&old, &new).unwrap();
+/// assert_eq!(patch(&old, &delta).unwrap(), new);
+/// ```
+pub fn diff(old: &[u8], new: &[u8]) -> Option<Vec<u8>> {
+    let suffix_array = crate::divsufsort(old)?;
+    Some(diff_with_suffix_array(old, &suffix_array, new))
+}
+
+/// Creates a patch from `old` to `new` with the suffix array of `old`, e.g. from [divsufsort64](crate::divsufsort64)  
+/// or reused for many new buffers.
+pub fn diff_with_suffix_array<S: SuffixArray + ?Sized>(old: &[u8], suffix_array: &S, new: &[u8]) -> Vec<u8> {
+    assert_eq!(old.len(), suffix_array.len(), "lengths of the old buffer and the suffix array are different");
+    let (old_size, new_size) = (old.len() as isize, new.len() as isize);
+    let mut controls = Vec::new();
+    let mut diff_block = Vec::new();
+    let mut extra_block = Vec::new();
+
+    let (mut scan, mut position, mut length) = (0isize, 0isize, 0isize);
+    let (mut last_scan, mut last_position, mut last_offset) = (0isize, 0isize, 0isize);
+    while scan < new_size {
+        let mut old_score = 0;
+        scan += length;
+        let mut scsc = scan;
+        while scan < new_size {
+            let (found_length, found_position) = if old.is_empty() {
+                (0, 0)
+            } else {
+                search(suffix_array, old, &new[scan as usize..], 0, old.len() - 1)
+            };
+            length = found_length as isize;
+            position = found_position as isize;
+            while scsc < scan + length {
+                if scsc + last_offset < old_size && old[(scsc + last_offset) as usize] == new[scsc as usize] {
+                    old_score += 1;
+                }
+                scsc += 1;
+            }
+            if (length == old_score && length != 0) || length > old_score + 8 {
+                break;
+            }
+            if scan + last_offset < old_size && old[(scan + last_offset) as usize] == new[scan as usize] {
+                old_score -= 1;
+            }
+            scan += 1;
+        }
+
+        if length != old_score || scan == new_size {
+            // Extend the previous match forwards
+            let (mut s, mut best, mut length_forward) = (0isize, 0isize, 0isize);
+            let mut i = 0;
+            while last_scan + i < scan && last_position + i < old_size {
+                if old[(last_position + i) as usize] == new[(last_scan + i) as usize] {
+                    s += 1;
+                }
+                i += 1;
+                if s * 2 - i > best * 2 - length_forward {
+                    best = s;
+                    length_forward = i;
+                }
+            }
+            // Extend the current match backwards
+            let mut length_backward = 0isize;
+            if scan < new_size {
+                let (mut s, mut best) = (0isize, 0isize);
+                let mut i = 1;
+                while scan >= last_scan + i && position >= i {
+                    if old[(position - i) as usize] == new[(scan - i) as usize] {
+                        s += 1;
+                    }
+                    if s * 2 - i > best * 2 - length_backward {
+                        best = s;
+                        length_backward = i;
+                    }
+                    i += 1;
+                }
+            }
+            // Split the overlap of both extensions
+            if last_scan + length_forward > scan - length_backward {
+                let overlap = (last_scan + length_forward) - (scan - length_backward);
+                let (mut s, mut best, mut split) = (0isize, 0isize, 0isize);
+                for i in 0..overlap {
+                    let forward = last_scan + length_forward - overlap + i;
+                    if new[forward as usize] == old[(last_position + length_forward - overlap + i) as usize] {
+                        s += 1;
+                    }
+                    if new[(scan - length_backward + i) as usize] == old[(position - length_backward + i) as usize] {
+                        s -= 1;
+                    }
+                    if s > best {
+                        best = s;
+                        split = i + 1;
+                    }
+                }
+                length_forward += split - overlap;
+                length_backward -= split;
+            }
+
+            for i in 0..length_forward {
+                let (new_byte, old_byte) = (new[(last_scan + i) as usize], old[(last_position + i) as usize]);
+                diff_block.push(new_byte.wrapping_sub(old_byte));
+            }
+            let extra_start = (last_scan + length_forward) as usize;
+            let extra_end = (scan - length_backward) as usize;
+            extra_block.extend_from_slice(&new[extra_start..extra_end]);
+            controls.push((
+                length_forward as u64,
+                (extra_end - extra_start) as u64,
+                ((position - length_backward) - (last_position + length_forward)) as i64,
+            ));
+
+            last_scan = scan - length_backward;
+            last_position = position - length_backward;
+            last_offset = position - scan;
+        }
+    }
+
+    let mut patch = Vec::with_capacity(HEADER_LENGTH + 24 * controls.len() + diff_block.len() + extra_block.len());
+    patch.extend_from_slice(MAGIC);
+    write_u64(&mut patch, 24 * controls.len() as u64);
+    write_u64(&mut patch, diff_block.len() as u64);
+    write_u64(&mut patch, new.len() as u64);
+    for (add, copy, seek) in controls {
+        write_u64(&mut patch, add);
+        write_u64(&mut patch, copy);
+        write_u64(&mut patch, seek as u64);
+    }
+    patch.extend_from_slice(&diff_block);
+    patch.extend_from_slice(&extra_block);
+    patch
+}
+
+/// The reason [patch] failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeltaError {
+    /// The patch does not start with [MAGIC].
+    BadMagic,
+    /// The lengths in the header do not match the length of the patch.
+    Truncated,
+    /// A control reads out of the blocks or writes out of the new buffer.
+    Corrupt { control: usize },
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::BadMagic => write!(f, "Not a patch. Magic bytes are different"),
+            Self::Truncated => write!(f, "Patch is truncated or has wrong block lengths"),
+            Self::Corrupt { control } => write!(f, "Patch is corrupt at control {}", control),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeltaError {}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Applies a patch of [diff] to `old`.  
+/// Output: The new buffer, or the reason the patch is invalid.
+pub fn patch(old: &[u8], patch: &[u8]) -> Result<Vec<u8>, DeltaError> {
+    if patch.len() < HEADER_LENGTH {
+        return Err(if patch.starts_with(&MAGIC[..patch.len().min(8)]) { DeltaError::Truncated } else { DeltaError::BadMagic });
+    }
+    if &patch[..8] != MAGIC {
+        return Err(DeltaError::BadMagic);
+    }
+    let (control_length, diff_length, new_size) = (read_u64(patch, 8), read_u64(patch, 16), read_u64(patch, 24));
+    let body = (patch.len() - HEADER_LENGTH) as u64;
+    // The extra block is the rest, so the new buffer is never larger than the patch
+    if control_length % 24 != 0
+        || control_length > body
+        || diff_length > body - control_length
+        || new_size != body - control_length
+    {
+        return Err(DeltaError::Truncated);
+    }
+    let (control_length, diff_length, new_size) = (control_length as usize, diff_length as usize, new_size as usize);
+    let controls = &patch[HEADER_LENGTH..HEADER_LENGTH + control_length];
+    let diff_block = &patch[HEADER_LENGTH + control_length..HEADER_LENGTH + control_length + diff_length];
+    let extra_block = &patch[HEADER_LENGTH + control_length + diff_length..];
+
+    let mut new = Vec::with_capacity(new_size);
+    let (mut old_position, mut diff_position, mut extra_position) = (0i64, 0usize, 0usize);
+    for (index, control) in controls.chunks(24).enumerate() {
+        let corrupt = DeltaError::Corrupt { control: index };
+        let (add, copy, seek) = (read_u64(control, 0), read_u64(control, 8), read_u64(control, 16) as i64);
+        if add > (diff_block.len() - diff_position) as u64 || copy > (extra_block.len() - extra_position) as u64 {
+            return Err(corrupt);
+        }
+        let (add, copy) = (add as usize, copy as usize);
+        for (i, &difference) in diff_block[diff_position..diff_position + add].iter().enumerate() {
+            let position = old_position.checked_add(i as i64).ok_or_else(|| corrupt.clone())?;
+            let old_byte = if 0 <= position && position < old.len() as i64 { old[position as usize] } else { 0 };
+            new.push(old_byte.wrapping_add(difference));
+        }
+        new.extend_from_slice(&extra_block[extra_position..extra_position + copy]);
+        diff_position += add;
+        extra_position += copy;
+        old_position = old_position
+            .checked_add(add as i64)
+            .and_then(|position| position.checked_add(seek))
+            .ok_or(corrupt)?;
+    }
+    if diff_position != diff_block.len() || extra_position != extra_block.len() {
+        return Err(DeltaError::Truncated);
+    }
+    Ok(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Lcg;
+
+    fn random_bytes(rng: &mut Lcg, length: usize) -> Vec<u8> {
+        (0..length).map(|_| (rng.next_u64() >> 56) as u8).collect()
+    }
+
+    #[test]
+    fn test_diff_and_patch() {
+        let mut rng = Lcg::new(7);
+        let old = random_bytes(&mut rng, 20000);
+        // Move blocks, change some bytes and insert new bytes
+        let mut new = Vec::new();
+        new.extend_from_slice(&old[5000..12000]);
+        new.extend_from_slice(&random_bytes(&mut rng, 300));
+        new.extend_from_slice(&old[0..5000]);
+        new.extend_from_slice(&old[15000..]);
+        for i in (0..new.len()).step_by(997) {
+            new[i] = new[i].wrapping_add(1);
+        }
+
+        let delta = diff(&old, &new).unwrap();
+        assert_eq!(patch(&old, &delta).unwrap(), new);
+        assert!(delta.len() < new.len() / 4 * 5);
+        let zeros = delta.iter().filter(|&&b| b == 0).count();
+        assert!(zeros > new.len() / 2);
+
+        let suffix_array_64 = crate::divsufsort64(&old).unwrap();
+        assert_eq!(diff_with_suffix_array(&old, &suffix_array_64, &new), delta);
+
+        for (old, new) in [(&b""[..], &b"new"[..]), (b"old", b""), (b"", b""), (b"a", b"aaaaaaaa"), (b"abcabc", b"abc")] {
+            assert_eq!(patch(old, &diff(old, new).unwrap()).unwrap(), new);
+        }
+    }
+
+    #[test]
+    fn test_invalid_patches() {
+        let old = b"the quick brown fox jumps over the lazy dog";
+        let new = b"the quick red fox jumps over the lazy cat";
+        let delta = diff(old, new).unwrap();
+
+        assert_eq!(patch(old, b"BSDIFF40"), Err(DeltaError::BadMagic));
+        assert_eq!(patch(old, &delta[..HEADER_LENGTH - 1]), Err(DeltaError::Truncated));
+        assert_eq!(patch(old, &delta[..delta.len() - 1]), Err(DeltaError::Truncated));
+
+        // The first `add` reading past the diff block
+        let mut corrupted = delta.clone();
+        corrupted[HEADER_LENGTH..HEADER_LENGTH + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(patch(old, &corrupted), Err(DeltaError::Corrupt { control: 0 }));
+    }
+}
@@ -14,7 +14,7 @@ extern "C" {
 
     pub fn divbwt(T: *const sauchar_t, U: *mut sauchar_t, A: *mut saidx_t, n: saidx_t) -> saidx_t;
     
-    pub fn divsufsort_version() -> *const ::std::os::raw::c_char;
+    pub fn divsufsort_version() -> *const sauchar_t;
     
     pub fn bw_transform(
         T: *const sauchar_t,
@@ -19,7 +19,7 @@ extern "C" {
         n: saidx64_t,
     ) -> saidx64_t;
 
-    pub fn divsufsort64_version() -> *const ::std::os::raw::c_char;
+    pub fn divsufsort64_version() -> *const sauchar_t;
 
     pub fn bw_transform64(
         T: *const sauchar_t,
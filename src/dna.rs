@@ -0,0 +1,177 @@
+//! Index of both strands of double-stranded DNA.
+//!
+//! The suffix array of [divsufsort64](crate::divsufsort64) is constructed from the sequence, a separator and
+//! its reverse complement, so a single search finds the hits on both strands.
+//! Soft-masked (lower-case) bases are indexed as upper-case bases, and other symbols such as `N` never match.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::search::equal_range;
+
+const SEPARATOR: u8 = b'$';
+
+/// Upper-case base, or `N` for any other symbol.
+#[inline]
+fn normalize(base: u8) -> u8 {
+    match base.to_ascii_uppercase() {
+        upper @ (b'A' | b'C' | b'G' | b'T') => upper,
+        _ => b'N',
+    }
+}
+
+#[inline]
+fn complement(base: u8) -> u8 {
+    match base {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' => b'A',
+        other => other,
+    }
+}
+
+/// Reverse complement of a DNA sequence, normalized to upper-case `A`, `C`, `G`, `T` and `N`.
+pub fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+    sequence.iter().rev().map(|&base| complement(normalize(base))).collect()
+}
+
+/// Strand of a hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+/// An occurrence of a pattern on either strand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DnaHit {
+    /// Start of the occurrence in forward-strand coordinates.  
+    /// For a reverse-strand hit, the reverse complement of the pattern occurs at this position of the forward strand.
+    pub position: usize,
+    pub strand: Strand,
+}
+
+/// Suffix array of a DNA sequence and its reverse complement.
+#[derive(Clone, Debug)]
+pub struct DnaIndex {
+    /// Forward strand, the separator and the reverse strand.
+    text: Vec<u8>,
+    suffix_array: Vec<i64>,
+    sequence_length: usize,
+}
+
+impl DnaIndex {
+    /// Constructs the index of both strands of `sequence`.
+    pub fn new(sequence: &[u8]) -> Option<Self> {
+        let sequence_length = sequence.len();
+        let mut text = Vec::with_capacity(2 * sequence_length + 1);
+        text.extend(sequence.iter().map(|&base| normalize(base)));
+        text.push(SEPARATOR);
+        text.extend(sequence.iter().rev().map(|&base| complement(normalize(base))));
+        let suffix_array = crate::divsufsort64(&text)?;
+        Some(Self { text, suffix_array, sequence_length })
+    }
+
+    /// Search for the pattern P on both strands.  
+    /// Input: Pattern of bases, in upper or lower case.  
+    /// Output: Hits in forward-strand coordinates, sorted by position and strand.  
+    /// A palindromic pattern is reported on both strands. An empty pattern or a pattern containing a symbol other than `ACGT` has no hits.
+    /// ```rust
+    /// use libdivsufsort_rs::dna::{DnaHit, DnaIndex, Strand};
+    ///
+    /// let index = DnaIndex::new(b"AACCGgtTNT").unwrap();
+    /// assert_eq!(
+    ///     index.search(b"ccg"),
+    ///     vec![
+    ///         DnaHit { position: 2, strand: Strand::Forward },
+    ///         DnaHit { position: 3, strand: Strand::Reverse },
+    ///     ]
+    /// );
+    /// assert!(index.search(b"TNT").is_empty());
+    /// ```
+    pub fn search(&self, pattern: &[u8]) -> Vec<DnaHit> {
+        let n = self.sequence_length;
+        let mut hits: Vec<DnaHit> = self.suffix_array[self.ranks(pattern)]
+            .iter()
+            .map(|&position| {
+                let position = position as usize;
+                if position < n {
+                    DnaHit { position, strand: Strand::Forward }
+                } else {
+                    // `[r, r + m)` of the reverse strand is `[n - r - m, n - r)` of the forward strand
+                    let r = position - (n + 1);
+                    DnaHit { position: n - r - pattern.len(), strand: Strand::Reverse }
+                }
+            })
+            .collect();
+        hits.sort_unstable();
+        hits
+    }
+    /// Number of hits on both strands.
+    pub fn count(&self, pattern: &[u8]) -> usize {
+        self.ranks(pattern).len()
+    }
+    fn ranks(&self, pattern: &[u8]) -> Range<usize> {
+        let pattern: Vec<u8> = pattern.iter().map(|&base| normalize(base)).collect();
+        if pattern.is_empty() || pattern.contains(&b'N') {
+            return 0..0;
+        }
+        let (lo, hi) = equal_range(&self.text, &pattern, &self.suffix_array);
+        lo..hi
+    }
+
+    /// Length of the forward strand.
+    pub fn sequence_length(&self) -> usize {
+        self.sequence_length
+    }
+    /// Normalized forward strand, `$` and the reverse strand.
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+    /// Suffix array of [text](Self::text).
+    pub fn suffix_array(&self) -> &[i64] {
+        &self.suffix_array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    #[test]
+    fn test_search() {
+        // Soft-mask a region and put some `N`s
+        let mut sequence = INPUT.to_vec();
+        sequence[40..60].make_ascii_lowercase();
+        sequence[100..104].copy_from_slice(b"NNNN");
+        let index = DnaIndex::new(&sequence).unwrap();
+        let upper = sequence.to_ascii_uppercase();
+
+        for pattern in [&b"CCGG"[..], b"GTAC", b"TTTAC", b"gaaac", b"CCANNNN", b"N", b"ACGTACGTACGT", b"C"] {
+            let pattern_upper = pattern.to_ascii_uppercase();
+            let reverse = reverse_complement(pattern);
+            let mut expected = Vec::new();
+            if !pattern_upper.contains(&b'N') {
+                for (position, window) in upper.windows(pattern.len()).enumerate() {
+                    if window == &pattern_upper[..] {
+                        expected.push(DnaHit { position, strand: Strand::Forward });
+                    }
+                    if window == &reverse[..] {
+                        expected.push(DnaHit { position, strand: Strand::Reverse });
+                    }
+                }
+            }
+            assert_eq!(index.search(pattern), expected);
+            assert_eq!(index.count(pattern), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_reverse_complement() {
+        assert_eq!(reverse_complement(b"AACgtnX"), b"NNACGTT");
+        assert!(DnaIndex::new(INPUT).unwrap().search(b"").is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+//! Enhanced suffix array: the suffix array, the LCP array and the child table
+//! (Abouelhoda, Kurtz & Ohlebusch 2004).
+//!
+//! The internal nodes of the suffix tree are the lcp-intervals of the suffix array: ranges of suffixes
+//! sharing a prefix of `depth` symbols, split into child intervals by the next symbol.
+//! The texts are not terminated by a sentinel, so a suffix that is a prefix of another suffix is a leaf
+//! whose depth is the depth of its parent.
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::sa::SaIndex;
+use crate::search::Positions;
+
+/// A node of the suffix tree as a range of suffix array indices.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LcpInterval {
+    /// Length of the common prefix of the suffixes in the interval (the length of the suffix for a leaf).
+    pub depth: usize,
+    pub ranks: Range<usize>,
+}
+
+impl LcpInterval {
+    /// Whether the interval is a single suffix.
+    pub fn is_leaf(&self) -> bool {
+        self.ranks.len() == 1
+    }
+}
+
+/// Suffix array, LCP array and child table.
+#[derive(Clone, Debug)]
+pub struct EnhancedSuffixArray<I: SaIndex = i32> {
+    suffix_array: Vec<I>,
+    lcp: Vec<I>,
+    /// `up[j + 1]` is the first l-index of an interval ending at `j`, if it is the first child of its parent.
+    up: Vec<I>,
+    /// `down[i]` is the first l-index of an interval starting at `i`, otherwise.
+    down: Vec<I>,
+    /// Next l-index of the same interval.
+    next_l_index: Vec<I>,
+}
+
+impl EnhancedSuffixArray<i32> {
+    /// Constructs the enhanced suffix array with [divsufsort](crate::divsufsort).
+    pub fn new(input_string: &[u8]) -> Option<Self> {
+        let suffix_array = crate::divsufsort(input_string)?;
+        let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+        Some(Self::from_parts(suffix_array, lcp))
+    }
+}
+
+impl EnhancedSuffixArray<i64> {
+    /// 64-bit version of [new](EnhancedSuffixArray::new)
+    pub fn new64(input_string: &[u8]) -> Option<Self> {
+        let suffix_array = crate::divsufsort64(input_string)?;
+        let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+        Some(Self::from_parts(suffix_array, lcp))
+    }
+}
+
+impl<I: SaIndex> EnhancedSuffixArray<I> {
+    /// Constructs the child table of a suffix array and its LCP array (see [lcp_array](crate::lcp::lcp_array)) in `O(n)`.
+    pub fn from_parts(suffix_array: Vec<I>, lcp: Vec<I>) -> Self {
+        assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+        let n = suffix_array.len();
+        let mut esa = Self {
+            suffix_array,
+            lcp,
+            up: vec![I::EMPTY; n + 1],
+            down: vec![I::EMPTY; n + 1],
+            next_l_index: vec![I::EMPTY; n + 1],
+        };
+
+        // The bottom of the stacks is index 0, whose LCP value -1 is never popped
+        let mut stack = vec![0];
+        let mut last_index = None;
+        for i in 1..=n {
+            while esa.lcp_at(i) < esa.lcp_at(*stack.last().unwrap()) {
+                let popped = stack.pop().unwrap();
+                last_index = Some(popped);
+                let top = *stack.last().unwrap();
+                if esa.lcp_at(i) <= esa.lcp_at(top) && esa.lcp_at(top) != esa.lcp_at(popped) {
+                    esa.down[top] = I::from_usize(popped);
+                }
+            }
+            if let Some(last_index) = last_index.take() {
+                esa.up[i] = I::from_usize(last_index);
+            }
+            stack.push(i);
+        }
+
+        let mut stack = vec![0];
+        for i in 1..=n {
+            while esa.lcp_at(i) < esa.lcp_at(*stack.last().unwrap()) {
+                stack.pop();
+            }
+            if esa.lcp_at(i) == esa.lcp_at(*stack.last().unwrap()) {
+                let previous = stack.pop().unwrap();
+                esa.next_l_index[previous] = I::from_usize(i);
+            }
+            stack.push(i);
+        }
+        esa
+    }
+
+    /// LCP value at `i`, with -1 at both ends.
+    #[inline]
+    fn lcp_at(&self, i: usize) -> isize {
+        if i == 0 || i >= self.lcp.len() {
+            -1
+        } else {
+            self.lcp[i].to_usize() as isize
+        }
+    }
+    #[inline]
+    fn entry(table: &[I], i: usize) -> Option<usize> {
+        table.get(i).filter(|&&value| value != I::EMPTY).map(|value| value.to_usize())
+    }
+    /// First l-index of the non-singleton interval `ranks`.
+    fn first_l_index(&self, ranks: &Range<usize>) -> usize {
+        match Self::entry(&self.up, ranks.end) {
+            Some(up) if ranks.start < up && up < ranks.end => up,
+            _ => Self::entry(&self.down, ranks.start).unwrap(),
+        }
+    }
+    fn node(&self, ranks: Range<usize>) -> LcpInterval {
+        let depth = if ranks.len() == 1 {
+            self.suffix_array.len() - self.suffix_array[ranks.start].to_usize()
+        } else {
+            self.lcp[self.first_l_index(&ranks)].to_usize()
+        };
+        LcpInterval { depth, ranks }
+    }
+
+    /// Length of the text.
+    pub fn len(&self) -> usize {
+        self.suffix_array.len()
+    }
+    /// Whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.suffix_array.is_empty()
+    }
+    /// Suffix array of the text.
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+    /// LCP array of the text.
+    pub fn lcp(&self) -> &[I] {
+        &self.lcp
+    }
+
+    /// Interval of all suffixes, `None` for an empty text.
+    pub fn root(&self) -> Option<LcpInterval> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.node(0..self.len()))
+        }
+    }
+    /// Child intervals of `interval` in lexicographic order, by the child table in `O(number of children)`.  
+    /// A leaf has no children.
+    /// ```rust
+    /// use libdivsufsort_rs::esa::EnhancedSuffixArray;
+    ///
+    /// let esa = EnhancedSuffixArray::new(b"abracadabra").unwrap();
+    /// let root = esa.root().unwrap();
+    /// // a, bra, cadabra, dabra, ra
+    /// let children = esa.children(&root);
+    /// assert_eq!(children.len(), 5);
+    /// assert_eq!((children[0].depth, children[0].ranks.clone()), (1, 0..5));
+    /// ```
+    pub fn children(&self, interval: &LcpInterval) -> Vec<LcpInterval> {
+        if interval.ranks.len() <= 1 {
+            return Vec::new();
+        }
+        let mut children = Vec::new();
+        let mut start = interval.ranks.start;
+        let mut l_index = Some(self.first_l_index(&interval.ranks));
+        while let Some(end) = l_index {
+            children.push(self.node(start..end));
+            start = end;
+            l_index = Self::entry(&self.next_l_index, end).filter(|&next| next < interval.ranks.end);
+        }
+        children.push(self.node(start..interval.ranks.end));
+        children
+    }
+    /// Text positions of the suffixes in `interval` (the leaves below the node).
+    pub fn leaves(&self, interval: &LcpInterval) -> Positions<'_, [I]> {
+        Positions::new(&self.suffix_array[..], interval.ranks.clone())
+    }
+    /// Iterates the non-singleton lcp-intervals bottom-up, each after all intervals nested in it.
+    pub fn intervals_bottom_up(&self) -> BottomUpIntervals<'_, I> {
+        BottomUpIntervals { esa: self, stack: Vec::new(), i: 1, lower_bound: 0 }
+    }
+}
+
+/// Iterator over the lcp-intervals in post-order, returned by [EnhancedSuffixArray::intervals_bottom_up].
+#[derive(Clone, Debug)]
+pub struct BottomUpIntervals<'a, I: SaIndex> {
+    esa: &'a EnhancedSuffixArray<I>,
+    /// Open intervals as (depth, first rank), with increasing depths
+    stack: Vec<(usize, usize)>,
+    i: usize,
+    lower_bound: usize,
+}
+
+impl<I: SaIndex> Iterator for BottomUpIntervals<'_, I> {
+    type Item = LcpInterval;
+
+    fn next(&mut self) -> Option<LcpInterval> {
+        while self.i <= self.esa.len() {
+            let lcp = self.esa.lcp_at(self.i);
+            if let Some(&(depth, start)) = self.stack.last() {
+                if lcp < depth as isize {
+                    self.stack.pop();
+                    self.lower_bound = start;
+                    return Some(LcpInterval { depth, ranks: start..self.i });
+                }
+            }
+            if lcp >= 0 && self.stack.last().map_or(true, |&(depth, _)| (depth as isize) < lcp) {
+                self.stack.push((lcp as usize, self.lower_bound));
+            }
+            self.i += 1;
+            self.lower_bound = self.i - 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGG";
+
+    /// All non-singleton lcp-intervals by the definition.
+    fn naive_intervals(lcp: &[i32]) -> Vec<LcpInterval> {
+        let n = lcp.len();
+        let lcp_at = |i: usize| if i == 0 || i >= n { -1 } else { lcp[i] as isize };
+        let mut intervals = Vec::new();
+        for start in 0..n {
+            for end in start + 2..=n {
+                let depth = (start + 1..end).map(lcp_at).min().unwrap();
+                if lcp_at(start) < depth && lcp_at(end) < depth {
+                    intervals.push(LcpInterval { depth: depth as usize, ranks: start..end });
+                }
+            }
+        }
+        intervals
+    }
+
+    fn sorted(mut intervals: Vec<LcpInterval>) -> Vec<LcpInterval> {
+        intervals.sort_unstable_by_key(|interval| (interval.ranks.start, interval.ranks.end));
+        intervals
+    }
+
+    #[test]
+    fn test_traversals() {
+        for input_string in [INPUT, b"AAAAAA", b"ABABABA", b"A", b"AB", b""] {
+            let esa = EnhancedSuffixArray::new(input_string).unwrap();
+            let expected = sorted(naive_intervals(esa.lcp()));
+
+            // Bottom-up: every interval comes after the intervals nested in it
+            let bottom_up: Vec<LcpInterval> = esa.intervals_bottom_up().collect();
+            for (index, interval) in bottom_up.iter().enumerate() {
+                assert!(bottom_up[index + 1..].iter().all(|later| {
+                    later.ranks.end <= interval.ranks.start
+                        || interval.ranks.end <= later.ranks.start
+                        || (later.ranks.start <= interval.ranks.start && interval.ranks.end <= later.ranks.end)
+                }));
+            }
+            assert_eq!(sorted(bottom_up), expected);
+
+            // Top-down from the root
+            let mut top_down = Vec::new();
+            let mut leaves = Vec::new();
+            let mut stack: Vec<LcpInterval> = esa.root().into_iter().collect();
+            while let Some(interval) = stack.pop() {
+                if interval.is_leaf() {
+                    let position = esa.suffix_array()[interval.ranks.start] as usize;
+                    assert_eq!(interval.depth, input_string.len() - position);
+                    leaves.push(position);
+                    continue;
+                }
+                let children = esa.children(&interval);
+                assert!(children.len() >= 2 || interval.ranks == (0..esa.len()));
+                assert_eq!(children.first().unwrap().ranks.start, interval.ranks.start);
+                assert_eq!(children.last().unwrap().ranks.end, interval.ranks.end);
+                for pair in children.windows(2) {
+                    assert_eq!(pair[0].ranks.end, pair[1].ranks.start);
+                }
+                for child in &children {
+                    assert!(child.depth > interval.depth || (child.is_leaf() && child.depth == interval.depth));
+                }
+                let mut positions: Vec<usize> = esa.leaves(&interval).collect();
+                positions.sort_unstable();
+                assert!(positions.windows(2).all(|w| {
+                    input_string[w[0]..w[0] + interval.depth] == input_string[w[1]..w[1] + interval.depth]
+                }));
+                top_down.push(interval);
+                stack.extend(children);
+            }
+            assert_eq!(sorted(top_down), expected);
+            leaves.sort_unstable();
+            assert_eq!(leaves, (0..input_string.len()).collect::<Vec<_>>());
+        }
+    }
+}
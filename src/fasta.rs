@@ -0,0 +1,253 @@
+//! FASTA and FASTQ readers and an index of many records.
+//!
+//! Sequences of the records are concatenated with `\n` (which never occurs in a sequence) between them,
+//! and the suffix array is constructed with [divsufsort64](crate::divsufsort64).
+//! Hits of [sa_search64](crate::sa_search64) are mapped back to `(record name, offset)` with the table of records.
+
+use std::io::{self, BufRead};
+
+use crate::search::equal_range;
+
+const SEPARATOR: u8 = b'\n';
+
+/// A named sequence read from a FASTA or FASTQ file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// First word of the header line.
+    pub name: String,
+    pub sequence: Vec<u8>,
+}
+
+fn invalid_data(line_number: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_number, message))
+}
+
+/// Name of a header line without its marker (`>` or `@`).
+fn header_name(header: &[u8]) -> String {
+    let name = header.split(|c| c.is_ascii_whitespace()).next().unwrap_or(&[]);
+    String::from_utf8_lossy(name).into_owned()
+}
+
+/// Reads lines without the line terminators (`\n` or `\r\n`), with their 1-based line numbers.
+fn lines<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<(usize, Vec<u8>)>> {
+    reader.split(b'\n').enumerate().map(|(index, line)| {
+        line.map(|mut line| {
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            (index + 1, line)
+        })
+    })
+}
+
+/// Reads the records of a FASTA file. Sequences may span multiple lines.  
+/// Output: `InvalidData` error if a sequence line comes before the first header.
+pub fn read_fasta<R: BufRead>(reader: R) -> io::Result<Vec<Record>> {
+    let mut records: Vec<Record> = Vec::new();
+    for line in lines(reader) {
+        let (line_number, line) = line?;
+        if let Some(header) = line.strip_prefix(b">") {
+            records.push(Record { name: header_name(header), sequence: Vec::new() });
+        } else if !line.iter().all(|c| c.is_ascii_whitespace()) {
+            let record = records
+                .last_mut()
+                .ok_or_else(|| invalid_data(line_number, "sequence before the first header"))?;
+            record.sequence.extend(line.iter().filter(|c| !c.is_ascii_whitespace()));
+        }
+    }
+    Ok(records)
+}
+
+/// Reads the records of a FASTQ file of four lines per record. Qualities are checked and discarded.  
+/// Output: `InvalidData` error if a record is malformed.
+pub fn read_fastq<R: BufRead>(reader: R) -> io::Result<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut lines = lines(reader);
+    while let Some(line) = lines.next() {
+        let (line_number, header) = line?;
+        if header.is_empty() {
+            continue;
+        }
+        let name = match header.strip_prefix(b"@") {
+            Some(header) => header_name(header),
+            None => return Err(invalid_data(line_number, "header not starting with '@'")),
+        };
+        let mut next_line = || match lines.next() {
+            Some(line) => line,
+            None => Err(invalid_data(line_number, "truncated record")),
+        };
+        let (_, sequence) = next_line()?;
+        let (separator_number, separator) = next_line()?;
+        if !separator.starts_with(b"+") {
+            return Err(invalid_data(separator_number, "separator not starting with '+'"));
+        }
+        let (quality_number, quality) = next_line()?;
+        if quality.len() != sequence.len() {
+            return Err(invalid_data(quality_number, "lengths of the sequence and the quality are different"));
+        }
+        records.push(Record { name, sequence });
+    }
+    Ok(records)
+}
+
+/// A record in the concatenated text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordSpan {
+    pub name: String,
+    /// Position of the first base in the concatenated text.
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Suffix array of the concatenated sequences of many records.
+#[derive(Clone, Debug)]
+pub struct SequenceIndex {
+    text: Vec<u8>,
+    suffix_array: Vec<i64>,
+    records: Vec<RecordSpan>,
+}
+
+impl SequenceIndex {
+    /// Concatenates the sequences and constructs the suffix array.  
+    /// The sequence of each record is dropped once it is copied.
+    /// ```rust
+    /// use libdivsufsort_rs::fasta::{read_fasta, SequenceIndex};
+    ///
+    /// let fasta = b">chr1 first\nACGTAC\nGT\n>chr2\nTTACGT\n";
+    /// let index = SequenceIndex::new(read_fasta(&fasta[..]).unwrap()).unwrap();
+    /// assert_eq!(index.search(b"ACGT"), vec![("chr1", 0), ("chr1", 4), ("chr2", 2)]);
+    /// ```
+    pub fn new<I: IntoIterator<Item = Record>>(records: I) -> Option<Self> {
+        let mut text = Vec::new();
+        let mut spans = Vec::new();
+        for (index, record) in records.into_iter().enumerate() {
+            if index > 0 {
+                text.push(SEPARATOR);
+            }
+            spans.push(RecordSpan { name: record.name, start: text.len(), length: record.sequence.len() });
+            text.extend_from_slice(&record.sequence);
+        }
+        let suffix_array = crate::divsufsort64(&text)?;
+        Some(Self { text, suffix_array, records: spans })
+    }
+    /// Reads a FASTA file and constructs the index.
+    pub fn from_fasta<R: BufRead>(reader: R) -> io::Result<Self> {
+        Self::new(read_fasta(reader)?).ok_or_else(construction_error)
+    }
+    /// Reads a FASTQ file and constructs the index.
+    pub fn from_fastq<R: BufRead>(reader: R) -> io::Result<Self> {
+        Self::new(read_fastq(reader)?).ok_or_else(construction_error)
+    }
+
+    /// [sa_search64](crate::sa_search64) over the concatenated text.
+    pub fn sa_search64(&self, pattern: &[u8]) -> Option<(i64, i64)> {
+        crate::sa_search64(&self.text, pattern, &self.suffix_array)
+    }
+    /// Maps a position of the concatenated text to `(record name, offset)`.  
+    /// Output: `None` for a position of a separator or out of the text.
+    pub fn locate(&self, position: usize) -> Option<(&str, usize)> {
+        self.record_at(position).map(|(record, offset)| (&record.name[..], offset))
+    }
+    fn record_at(&self, position: usize) -> Option<(&RecordSpan, usize)> {
+        let index = self.records.partition_point(|record| record.start <= position).checked_sub(1)?;
+        let record = &self.records[index];
+        let offset = position - record.start;
+        if offset < record.length {
+            Some((record, offset))
+        } else {
+            None
+        }
+    }
+    /// Search for the pattern P in all records.  
+    /// Output: `(record name, offset)` of the hits, in order of records and offsets.  
+    /// Hits spanning two records are not reported.
+    pub fn search(&self, pattern: &[u8]) -> Vec<(&str, usize)> {
+        let (lo, hi) = equal_range(&self.text, pattern, &self.suffix_array);
+        let mut positions: Vec<usize> = self.suffix_array[lo..hi]
+            .iter()
+            .map(|&position| position as usize)
+            .collect();
+        positions.sort_unstable();
+        positions
+            .into_iter()
+            .filter_map(|position| {
+                let (record, offset) = self.record_at(position)?;
+                if offset + pattern.len() <= record.length {
+                    Some((&record.name[..], offset))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Spans of the records in [text](Self::text), in input order.
+    pub fn records(&self) -> &[RecordSpan] {
+        &self.records
+    }
+    /// Sequences of the records joined with `\n`.
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+    /// Suffix array of [text](Self::text).
+    pub fn suffix_array(&self) -> &[i64] {
+        &self.suffix_array
+    }
+}
+
+fn construction_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "failed to construct the suffix array")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTA: &[u8] = b">seq1 description\r\nCTCCGTACACCTGTTTCGTATCGG\r\nAACCGGTAAGTGAAATTTCC\r\n\r\n>seq2\nACATCGCCGGAAACCGTATATTGTCCATCC\n>empty\n>seq3\nGCTGCCGGTGGATCCGGCTCC\nTGCGTGGAAAACCAG\n";
+    const FASTQ: &[u8] = b"@read1 1:N:0\nACGTTGCA\n+\nIIIIIIII\n@read2\nGGCATTAC\n+read2\nIIIII#II\n";
+
+    #[test]
+    fn test_read_fasta() {
+        let records = read_fasta(FASTA).unwrap();
+        let names: Vec<&str> = records.iter().map(|record| &record.name[..]).collect();
+        assert_eq!(names, ["seq1", "seq2", "empty", "seq3"]);
+        assert_eq!(records[0].sequence, b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCC");
+        assert!(records[2].sequence.is_empty());
+        assert_eq!(read_fasta(&b"ACGT\n>seq\nACGT\n"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_fastq() {
+        let records = read_fastq(FASTQ).unwrap();
+        assert_eq!(records[0], Record { name: "read1".to_string(), sequence: b"ACGTTGCA".to_vec() });
+        assert_eq!(records[1].name, "read2");
+        for malformed in [&b"@read\nACGT\n+\nIII\n"[..], b"@read\nACGT\n", b"read\nACGT\n+\nIIII\n", b"@read\nACGT\n-\nIIII\n"] {
+            assert_eq!(read_fastq(malformed).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let records = read_fasta(FASTA).unwrap();
+        let index = SequenceIndex::from_fasta(FASTA).unwrap();
+        for pattern in [&b"CCGG"[..], b"TCC", b"GG", b"CCAG", b"CCACAT", b"A\nG", b"TTTT"] {
+            let mut expected = Vec::new();
+            for record in &records {
+                for (offset, window) in record.sequence.windows(pattern.len()).enumerate() {
+                    if window == pattern {
+                        expected.push((&record.name[..], offset));
+                    }
+                }
+            }
+            assert_eq!(index.search(pattern), expected);
+        }
+        // seq1 ends with `TCC` and seq2 starts with `ACA`
+        assert_eq!(index.locate(43), Some(("seq1", 43)));
+        assert_eq!(index.locate(44), None);
+        assert_eq!(index.locate(45), Some(("seq2", 0)));
+        assert_eq!(index.locate(index.text().len()), None);
+
+        let index = SequenceIndex::from_fastq(FASTQ).unwrap();
+        assert_eq!(index.search(b"CA"), vec![("read1", 6), ("read2", 2)]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Suffix arrays over integer alphabets (`u16` or `u32` symbols, e.g. token ids).
+//!
+//! `libdivsufsort` only sorts bytes, so construction uses SA-IS written in Rust.
+//! The functions keep the names and semantics of their byte counterparts in the crate root.
+
+use alloc::{vec, vec::Vec};
+
+use crate::sa::{SaIndex, Symbol};
+use crate::sais::sais;
+use crate::search::equal_range;
+
+fn construct<T: Symbol, I: SaIndex>(input_string: &[T]) -> Option<Vec<I>> {
+    let n = input_string.len();
+    if n > I::MAX_LENGTH {
+        return None;
+    }
+    let mut sa = vec![I::EMPTY; n];
+    let alphabet_size = input_string.iter().map(|c| c.rank() + 1).max().unwrap_or(0);
+    if alphabet_size <= n.max(256) {
+        sais(|i: usize| input_string[i].rank(), alphabet_size, &mut sa);
+    } else {
+        // Sparse alphabet: rename the symbols to their ranks among distinct symbols
+        let mut symbols: Vec<T> = input_string.to_vec();
+        symbols.sort_unstable();
+        symbols.dedup();
+        let renamed: Vec<I> = input_string
+            .iter()
+            .map(|c| I::from_usize(symbols.binary_search(c).unwrap()))
+            .collect();
+        let alphabet_size = symbols.len();
+        drop(symbols);
+        sais(|i: usize| renamed[i].to_usize(), alphabet_size, &mut sa);
+    }
+    Some(sa)
+}
+
+#[inline]
+/// "Constructs the suffix array of a given string."  
+/// Input: Vector of integer symbols  
+/// Output: Suffix array
+pub fn divsufsort<T: Symbol>(input_string: &[T]) -> Option<Vec<i32>> {
+    construct(input_string)
+}
+#[inline]
+/// 64-bit version of [divsufsort]
+pub fn divsufsort64<T: Symbol>(input_string: &[T]) -> Option<Vec<i64>> {
+    construct(input_string)
+}
+
+/// "Constructs the burrows-wheeler transformed string of a given string and suffix array."  
+/// Input: Vector of integer symbols & its suffix array  
+/// A input vector is transformed to burrows wheeler transformed string  
+/// Output: Primary index(usually $ sign) of burrows wheeler transformed string
+pub fn bw_transform<T: Symbol, I: SaIndex>(input_string: &mut [T], suffix_array: &[I]) -> Option<I> {
+    let n = input_string.len();
+    if suffix_array.len() != n {
+        return None;
+    }
+    if n == 0 {
+        return Some(I::from_usize(0));
+    }
+    let mut transformed = Vec::with_capacity(n);
+    transformed.push(input_string[n - 1]);
+    let mut primary_index = None;
+    for (i, &position) in suffix_array.iter().enumerate() {
+        match position.to_usize() {
+            0 => primary_index = Some(I::from_usize(i + 1)),
+            p if p < n => transformed.push(input_string[p - 1]),
+            _ => return None,
+        }
+    }
+    if transformed.len() != n {
+        return None;
+    }
+    input_string.copy_from_slice(&transformed);
+    primary_index
+}
+
+/// "Inverse BW-transforms a given BWTed string."  
+/// Input: Vector of integer symbols(burrows wheeler transformed) & its primary index  
+/// A input vector is transformed to original string  
+/// Output: If no error occured, get Some value of `unit`. Otherwise, `None`.
+pub fn inverse_bw_transform<T: Symbol, I: SaIndex>(input_string: &mut [T], primary_index: I) -> Option<()> {
+    let n = input_string.len();
+    if n == 0 {
+        return Some(());
+    }
+    let primary_index = primary_index.to_usize();
+    if primary_index == 0 || primary_index > n {
+        return None;
+    }
+    // Rows of the sorted rotations of `$T`: the last column is the BWT with `$` at `primary_index`
+    let last_symbol = |row: usize| match row {
+        0 => Some(input_string[0]),
+        r if r == primary_index => None,
+        r if r < primary_index => Some(input_string[r]),
+        r => Some(input_string[r - 1]),
+    };
+    // A stable sort of the last column gives the first column, i.e. the LF mapping
+    let mut first_to_last: Vec<usize> = (0..=n).collect();
+    first_to_last.sort_by_key(|&row| last_symbol(row));
+    let mut last_to_first = vec![0usize; n + 1];
+    for (first, &last) in first_to_last.iter().enumerate() {
+        last_to_first[last] = first;
+    }
+    drop(first_to_last);
+
+    let mut original = Vec::with_capacity(n);
+    let mut row = 0;
+    for _ in 0..n {
+        original.push(last_symbol(row)?);
+        row = last_to_first[row];
+    }
+    original.reverse();
+    input_string.copy_from_slice(&original);
+    Some(())
+}
+
+/// "Search for the pattern P in the string T."  
+/// Input: Vector of integer symbols & its suffix array and vector of pattern string.  
+/// Output: tuple of index of suffix array for matched pattern and pattern count  
+/// Even with multiple counts, only one index is output.
+pub fn sa_search<T: Symbol>(input_string: &[T], pattern: &[T], suffix_array: &[i32]) -> Option<(i32, i32)> {
+    if suffix_array.len() > input_string.len() {
+        return None;
+    }
+    let (lo, hi) = equal_range(input_string, pattern, suffix_array);
+    Some((lo as i32, (hi - lo) as i32))
+}
+/// 64-bit version of [sa_search]
+pub fn sa_search64<T: Symbol>(input_string: &[T], pattern: &[T], suffix_array: &[i64]) -> Option<(i64, i64)> {
+    if suffix_array.len() > input_string.len() {
+        return None;
+    }
+    let (lo, hi) = equal_range(input_string, pattern, suffix_array);
+    Some((lo as i64, (hi - lo) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Lcg;
+
+    fn tokens() -> Vec<u32> {
+        let mut rng = Lcg::new(42);
+        // Sparse, large token ids
+        (0..500).map(|_| [7, 70_000, 3_000_000_000, 12, 70_000][rng.below(5)]).collect()
+    }
+
+    #[test]
+    fn test_divsufsort() {
+        let input_string = tokens();
+        let suffix_array = divsufsort(&input_string).unwrap();
+        let mut naive: Vec<i32> = (0..input_string.len() as i32).collect();
+        naive.sort_by(|&a, &b| input_string[a as usize..].cmp(&input_string[b as usize..]));
+        assert_eq!(suffix_array, naive);
+
+        let suffix_array_64 = divsufsort64(&input_string).unwrap();
+        assert!(suffix_array.iter().zip(&suffix_array_64).all(|(&a, &b)| a as i64 == b));
+
+        // Same as libdivsufsort for bytes
+        let bytes = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGG";
+        let symbols: Vec<u16> = bytes.iter().map(|&c| c as u16).collect();
+        assert_eq!(divsufsort(&symbols), crate::divsufsort(bytes));
+    }
+
+    #[test]
+    fn test_bw_transform() {
+        let input_string = tokens();
+
+        // Same as libdivsufsort for bytes
+        let mut bytes = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGG".to_vec();
+        let mut symbols: Vec<u16> = bytes.iter().map(|&c| c as u16).collect();
+        let suffix_array = divsufsort(&symbols).unwrap();
+        let pidx = bw_transform(&mut symbols, &suffix_array).unwrap();
+        assert_eq!(Some(pidx), crate::divbwt(&mut bytes));
+        assert!(symbols.iter().zip(&bytes).all(|(&a, &b)| a == b as u16));
+
+        // Inverse
+        let mut bwt = input_string.clone();
+        let suffix_array_64 = divsufsort64(&bwt).unwrap();
+        let pidx = bw_transform(&mut bwt, &suffix_array_64).unwrap();
+        inverse_bw_transform(&mut bwt, pidx).unwrap();
+        assert_eq!(bwt, input_string);
+    }
+
+    #[test]
+    fn test_sa_search() {
+        let input_string = tokens();
+        let pattern = [70_000, 3_000_000_000, 7];
+        let naive_count = input_string.windows(3).filter(|w| w == &pattern).count();
+
+        // 32
+        let suffix_array = divsufsort(&input_string).unwrap();
+        let (idx, count) = sa_search(&input_string, &pattern, &suffix_array).unwrap();
+        assert_eq!(count as usize, naive_count);
+        for i in idx..idx + count {
+            let position = suffix_array[i as usize] as usize;
+            assert_eq!(input_string[position..position + 3], pattern);
+        }
+
+        // 64
+        let suffix_array_64 = divsufsort64(&input_string).unwrap();
+        let (idx_64, count_64) = sa_search64(&input_string, &pattern, &suffix_array_64).unwrap();
+        assert_eq!((idx_64, count_64), (idx as i64, count as i64));
+    }
+}
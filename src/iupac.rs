@@ -0,0 +1,187 @@
+//! Search for DNA patterns with IUPAC ambiguity codes and `?` wildcards.
+//!
+//! An ambiguous position is expanded by branching the range of suffix array indices into the ranges of
+//! the bases it stands for, so only the concrete strings occurring in the text are enumerated.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use crate::sa::SuffixArray;
+use crate::search::{narrow, Children, Positions};
+
+/// Bases an IUPAC nucleotide code stands for (case-insensitive, `U` as `T`).  
+/// Output: `None` if `code` is not an IUPAC code.
+pub fn iupac_bases(code: u8) -> Option<&'static [u8]> {
+    let bases: &'static [u8] = match code.to_ascii_uppercase() {
+        b'A' => b"A",
+        b'C' => b"C",
+        b'G' => b"G",
+        b'T' | b'U' => b"T",
+        b'R' => b"AG",
+        b'Y' => b"CT",
+        b'S' => b"CG",
+        b'W' => b"AT",
+        b'K' => b"GT",
+        b'M' => b"AC",
+        b'B' => b"CGT",
+        b'D' => b"AGT",
+        b'H' => b"ACT",
+        b'V' => b"ACG",
+        b'N' => b"ACGT",
+        _ => return None,
+    };
+    Some(bases)
+}
+
+/// A concrete string matching a degenerate pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegenerateMatch {
+    /// Matched string in the text.
+    pub string: Vec<u8>,
+    /// Range of suffix array indices of the suffixes starting with `string`.
+    pub ranks: Range<usize>,
+}
+
+impl DegenerateMatch {
+    /// Text positions of the occurrences of the string.
+    pub fn positions<'a, S: SuffixArray + ?Sized>(&self, suffix_array: &'a S) -> Positions<'a, S> {
+        Positions::new(suffix_array, self.ranks.clone())
+    }
+}
+
+/// The reason [sa_search_degenerate] failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DegenerateError {
+    /// `pattern[index]` is neither an IUPAC code nor `?`.
+    InvalidCode { index: usize, code: u8 },
+    /// More than `limit` concrete strings are alive after expanding `pattern[..=index]`.
+    TooManyExpansions { index: usize, limit: usize },
+}
+
+impl fmt::Display for DegenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidCode { index, code } => {
+                write!(f, "Invalid IUPAC code. P[{}]={:?}", index, code as char)
+            }
+            Self::TooManyExpansions { index, limit } => {
+                write!(f, "More than {} expansions of P[..={}]", limit, index)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DegenerateError {}
+
+/// Search for the pattern P with IUPAC codes and `?` (any byte) in the string T.  
+/// Input: DNA sequence & its suffix array, the pattern and the maximum number of concrete strings to expand at once.  
+/// Codes match the upper-case bases they stand for. For example, `N` matches `A`, `C`, `G` and `T`, but not `N` in the text.  
+/// Output: Concrete strings occurring in the text with their ranges of suffix array indices, in lexicographic order.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, iupac::sa_search_degenerate};
+///
+/// let input_string = b"ACGTTGCAACGAAGCT";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let matches = sa_search_degenerate(input_string, b"ACR", &suffix_array, 16).unwrap();
+/// let strings: Vec<&[u8]> = matches.iter().map(|m| &m.string[..]).collect();
+/// assert_eq!(strings, [&b"ACG"[..]]);
+/// assert_eq!(matches[0].ranks.len(), 2);
+/// ```
+pub fn sa_search_degenerate<S: SuffixArray + ?Sized>(
+    input_string: &[u8],
+    pattern: &[u8],
+    suffix_array: &S,
+    max_expansions: usize,
+) -> Result<Vec<DegenerateMatch>, DegenerateError> {
+    let mut frontier = Vec::new();
+    if !suffix_array.is_empty() {
+        frontier.push(DegenerateMatch { string: Vec::new(), ranks: 0..suffix_array.len() });
+    }
+    for (depth, &code) in pattern.iter().enumerate() {
+        let bases = if code == b'?' {
+            None
+        } else {
+            Some(iupac_bases(code).ok_or(DegenerateError::InvalidCode { index: depth, code })?)
+        };
+        let mut next = Vec::new();
+        for branch in frontier {
+            let mut push = |symbol: u8, ranks: Range<usize>| {
+                let mut string = branch.string.clone();
+                string.push(symbol);
+                next.push(DegenerateMatch { string, ranks });
+            };
+            match bases {
+                Some(bases) => {
+                    for base in bases {
+                        let ranks = narrow(input_string, suffix_array, branch.ranks.clone(), depth, base);
+                        if !ranks.is_empty() {
+                            push(*base, ranks);
+                        }
+                    }
+                }
+                None => {
+                    for (&symbol, ranks) in Children::new(input_string, suffix_array, branch.ranks.clone(), depth) {
+                        push(symbol, ranks);
+                    }
+                }
+            }
+            if next.len() > max_expansions {
+                return Err(DegenerateError::TooManyExpansions { index: depth, limit: max_expansions });
+            }
+        }
+        frontier = next;
+    }
+    Ok(frontier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    fn is_match(window: &[u8], pattern: &[u8]) -> bool {
+        window.iter().zip(pattern).all(|(&c, &code)| code == b'?' || iupac_bases(code).unwrap().contains(&c))
+    }
+
+    #[test]
+    fn test_sa_search_degenerate() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        for pattern in [&b"ACGT"[..], b"RYN", b"ccwgg", b"G?A?C", b"NNNNN", b"?", b"TTTTTT", b"BDHV", b""] {
+            let matches = sa_search_degenerate(INPUT, pattern, &suffix_array, 1024).unwrap();
+            let strings: Vec<&[u8]> = matches.iter().map(|m| &m.string[..]).collect();
+            assert!(strings.windows(2).all(|w| w[0] < w[1]));
+
+            let mut positions: Vec<usize> = matches.iter().flat_map(|m| m.positions(&suffix_array)).collect();
+            positions.sort_unstable();
+            let expected: Vec<usize> = (0..INPUT.len())
+                .filter(|&p| INPUT.len() - p >= pattern.len() && is_match(&INPUT[p..], pattern))
+                .collect();
+            assert_eq!(positions, expected);
+            for m in &matches {
+                for position in m.positions(&suffix_array) {
+                    assert_eq!(&INPUT[position..position + pattern.len()], &m.string[..]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        assert_eq!(
+            sa_search_degenerate(INPUT, b"ACXT", &suffix_array, 16),
+            Err(DegenerateError::InvalidCode { index: 2, code: b'X' }),
+        );
+        assert_eq!(
+            sa_search_degenerate(INPUT, b"NNN", &suffix_array, 16),
+            Err(DegenerateError::TooManyExpansions { index: 2, limit: 16 }),
+        );
+        let mut distinct: Vec<&[u8]> = INPUT.windows(3).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(sa_search_degenerate(INPUT, b"NNN", &suffix_array, 64).unwrap().len(), distinct.len());
+    }
+}
@@ -0,0 +1,138 @@
+//! k-mer counting with the suffix array and the LCP array.
+//!
+//! Suffixes starting with the same k-mer are adjacent in the suffix array, and consecutive suffixes share
+//! their first k symbols exactly when their LCP is at least `k`. So one pass over both arrays enumerates
+//! the distinct k-mers with their counts, in lexicographic order and without hashing.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::sa::SaIndex;
+
+/// A distinct k-mer of the text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KmerCount {
+    /// Text position of an occurrence; the k-mer is `input_string[position..position + k]`.
+    pub position: usize,
+    /// Number of occurrences.
+    pub count: usize,
+    /// Range of suffix array indices of the occurrences.
+    pub ranks: Range<usize>,
+}
+
+/// Iterator over the distinct k-mers, returned by [kmer_counts].
+#[derive(Clone, Debug)]
+pub struct KmerCounts<'a, I> {
+    suffix_array: &'a [I],
+    lcp: &'a [I],
+    k: usize,
+    rank: usize,
+}
+
+impl<I: SaIndex> Iterator for KmerCounts<'_, I> {
+    type Item = KmerCount;
+
+    fn next(&mut self) -> Option<KmerCount> {
+        let n = self.suffix_array.len();
+        // Suffixes shorter than `k` do not start with a k-mer
+        while self.rank < n && n - self.suffix_array[self.rank].to_usize() < self.k {
+            self.rank += 1;
+        }
+        if self.rank == n {
+            return None;
+        }
+        let start = self.rank;
+        self.rank += 1;
+        while self.rank < n && self.lcp[self.rank].to_usize() >= self.k {
+            self.rank += 1;
+        }
+        Some(KmerCount {
+            position: self.suffix_array[start].to_usize(),
+            count: self.rank - start,
+            ranks: start..self.rank,
+        })
+    }
+}
+
+/// Counts the k-mers of a text.  
+/// Input: Suffix array & LCP array of the text (see [lcp_array](crate::lcp::lcp_array)) and `k`.  
+/// Output: Iterator over the distinct k-mers with their counts, in lexicographic order.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, kmer::kmer_counts, lcp::lcp_array};
+///
+/// let input_string = b"ACGTACGA";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let lcp = lcp_array(input_string, &suffix_array);
+/// let counts: Vec<(&[u8], usize)> = kmer_counts(&suffix_array, &lcp, 3)
+///     .map(|kmer| (&input_string[kmer.position..kmer.position + 3], kmer.count))
+///     .collect();
+/// assert_eq!(counts, vec![(&b"ACG"[..], 2), (b"CGA", 1), (b"CGT", 1), (b"GTA", 1), (b"TAC", 1)]);
+/// ```
+pub fn kmer_counts<'a, I: SaIndex>(suffix_array: &'a [I], lcp: &'a [I], k: usize) -> KmerCounts<'a, I> {
+    assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+    KmerCounts { suffix_array, lcp, k, rank: 0 }
+}
+
+/// Counts the k-mers occurring between `min_count` and `max_count` times (inclusive).
+pub fn kmer_counts_between<'a, I: SaIndex>(
+    suffix_array: &'a [I],
+    lcp: &'a [I],
+    k: usize,
+    min_count: usize,
+    max_count: usize,
+) -> impl Iterator<Item = KmerCount> + 'a {
+    kmer_counts(suffix_array, lcp, k).filter(move |kmer| min_count <= kmer.count && kmer.count <= max_count)
+}
+
+/// k-mer spectrum of a text.  
+/// Output: Histogram where `spectrum[c]` is the number of distinct k-mers occurring `c` times.  
+/// `spectrum[0]` is 0, and the last entry is non-zero unless there is no k-mer.
+pub fn kmer_spectrum<I: SaIndex>(suffix_array: &[I], lcp: &[I], k: usize) -> Vec<usize> {
+    let mut spectrum = Vec::new();
+    for kmer in kmer_counts(suffix_array, lcp, k) {
+        if spectrum.len() <= kmer.count {
+            spectrum.resize(kmer.count + 1, 0);
+        }
+        spectrum[kmer.count] += 1;
+    }
+    spectrum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    #[test]
+    fn test_kmer_counts() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let lcp = crate::lcp::lcp_array(INPUT, &suffix_array);
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        let lcp_64 = crate::lcp::lcp_array(INPUT, &suffix_array_64);
+        for k in [1, 2, 3, 5, 8, INPUT.len(), INPUT.len() + 1] {
+            let mut expected: BTreeMap<&[u8], usize> = BTreeMap::new();
+            for window in INPUT.windows(k) {
+                *expected.entry(window).or_insert(0) += 1;
+            }
+            let counts: Vec<(&[u8], usize)> = kmer_counts(&suffix_array, &lcp, k)
+                .map(|kmer| (&INPUT[kmer.position..kmer.position + k], kmer.count))
+                .collect();
+            assert_eq!(counts, expected.iter().map(|(&kmer, &count)| (kmer, count)).collect::<Vec<_>>());
+            assert!(kmer_counts(&suffix_array_64, &lcp_64, k).eq(kmer_counts(&suffix_array, &lcp, k)));
+
+            let mut spectrum = Vec::new();
+            for &count in expected.values() {
+                if spectrum.len() <= count {
+                    spectrum.resize(count + 1, 0);
+                }
+                spectrum[count] += 1;
+            }
+            assert_eq!(kmer_spectrum(&suffix_array, &lcp, k), spectrum);
+
+            let between = kmer_counts_between(&suffix_array, &lcp, k, 2, 3).count();
+            assert_eq!(between, expected.values().filter(|&&count| (2..=3).contains(&count)).count());
+        }
+    }
+}
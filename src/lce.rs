@@ -0,0 +1,95 @@
+//! Longest common extension (LCE) queries.
+//!
+//! The longest common prefix of the suffixes at `i` and `j` is the minimum of the LCP array
+//! between their ranks in the suffix array, answered in `O(1)` with a sparse table over the LCP array.
+
+use alloc::{vec, vec::Vec};
+
+use crate::rmq::SparseTable;
+use crate::sa::SaIndex;
+
+/// Inverse suffix array, LCP array and range minimum queries over it.
+#[derive(Clone, Debug)]
+pub struct LceIndex<I: SaIndex> {
+    inverse_suffix_array: Vec<I>,
+    lcp: Vec<I>,
+    rmq: SparseTable<I>,
+}
+
+impl<I: SaIndex> LceIndex<I> {
+    /// Constructs the index from a text and its suffix array, computing the LCP array.
+    pub fn new<T: Eq>(input_string: &[T], suffix_array: &[I]) -> Self {
+        Self::from_lcp(suffix_array, crate::lcp::lcp_array(input_string, suffix_array))
+    }
+    /// Constructs the index from a suffix array and its LCP array (see [lcp_array](crate::lcp::lcp_array)).
+    pub fn from_lcp(suffix_array: &[I], lcp: Vec<I>) -> Self {
+        assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+        let mut inverse_suffix_array = vec![I::from_usize(0); suffix_array.len()];
+        for (rank, &position) in suffix_array.iter().enumerate() {
+            inverse_suffix_array[position.to_usize()] = I::from_usize(rank);
+        }
+        let rmq = SparseTable::new(&lcp);
+        Self { inverse_suffix_array, lcp, rmq }
+    }
+
+    /// Length of the longest common prefix of the suffixes at text positions `i` and `j`, in `O(1)`.
+    /// ```rust
+    /// use libdivsufsort_rs::{divsufsort, lce::LceIndex};
+    ///
+    /// let input_string = b"abracadabra";
+    /// let suffix_array = divsufsort(input_string).unwrap();
+    /// let lce = LceIndex::new(input_string, &suffix_array);
+    /// assert_eq!(lce.lce(0, 7), 4); // "abra"
+    /// assert_eq!(lce.lce(3, 5), 1); // "a"
+    /// ```
+    pub fn lce(&self, i: usize, j: usize) -> usize {
+        let n = self.inverse_suffix_array.len();
+        assert!(i < n && j < n, "positions ({}, {}) are out of range for length {}", i, j, n);
+        if i == j {
+            return n - i;
+        }
+        let (a, b) = (self.inverse_suffix_array[i].to_usize(), self.inverse_suffix_array[j].to_usize());
+        let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+        let argmin = self.rmq.argmin(&self.lcp, lo + 1..hi + 1);
+        self.lcp[argmin].to_usize()
+    }
+    /// Length of the longest common prefix of the suffixes at ranks `a < b` of the suffix array, in `O(1)`.
+    pub fn lcp_of_ranks(&self, a: usize, b: usize) -> usize {
+        assert!(a < b && b < self.lcp.len(), "ranks ({}, {}) are not increasing or out of range", a, b);
+        self.lcp[self.rmq.argmin(&self.lcp, a + 1..b + 1)].to_usize()
+    }
+
+    /// Rank of the suffix at each text position.
+    pub fn inverse_suffix_array(&self) -> &[I] {
+        &self.inverse_suffix_array
+    }
+    /// LCP array, `lcp[r]` of the suffixes at ranks `r - 1` and `r`.
+    pub fn lcp(&self) -> &[I] {
+        &self.lcp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCC";
+
+    #[test]
+    fn test_lce() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        let lce = LceIndex::new(INPUT, &suffix_array);
+        let lce_64 = LceIndex::new(INPUT, &suffix_array_64);
+        for i in 0..INPUT.len() {
+            for j in 0..INPUT.len() {
+                let naive = INPUT[i..].iter().zip(&INPUT[j..]).take_while(|(a, b)| a == b).count();
+                assert_eq!(lce.lce(i, j), naive);
+                assert_eq!(lce_64.lce(i, j), naive);
+            }
+        }
+        let (a, b) = (3, 40);
+        let expected = lce.lce(suffix_array[a] as usize, suffix_array[b] as usize);
+        assert_eq!(lce.lcp_of_ranks(a, b), expected);
+    }
+}
@@ -0,0 +1,60 @@
+//! Longest common prefix (LCP) array.
+
+use alloc::{vec, vec::Vec};
+
+use crate::sa::SaIndex;
+
+/// Constructs the LCP array of a text and its suffix array (Kasai et al. 2001, with the Φ array).  
+/// Input: Text of any symbols & its suffix array  
+/// Output: LCP array, where `lcp[i]` is the length of the longest common prefix  
+/// of the suffixes at `suffix_array[i - 1]` and `suffix_array[i]`, and `lcp[0]` is 0.
+pub fn lcp_array<T: Eq, I: SaIndex>(input_string: &[T], suffix_array: &[I]) -> Vec<I> {
+    let n = suffix_array.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    // phi[suffix_array[i]] = suffix_array[i - 1]
+    let mut phi = vec![I::EMPTY; n];
+    for i in 1..n {
+        phi[suffix_array[i].to_usize()] = suffix_array[i - 1];
+    }
+    // Permuted LCP, computed in text order into `phi`
+    let mut length = 0;
+    for i in 0..n {
+        if phi[i] == I::EMPTY {
+            length = 0;
+            phi[i] = I::from_usize(0);
+            continue;
+        }
+        let j = phi[i].to_usize();
+        while i + length < n && j + length < n && input_string[i + length] == input_string[j + length] {
+            length += 1;
+        }
+        phi[i] = I::from_usize(length);
+        length = length.saturating_sub(1);
+    }
+    suffix_array.iter().map(|&position| phi[position.to_usize()]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcp_array() {
+        let input_string = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGG";
+        let suffix_array = crate::divsufsort(input_string).unwrap();
+        let lcp = lcp_array(input_string, &suffix_array);
+
+        assert_eq!(lcp[0], 0);
+        for i in 1..suffix_array.len() {
+            let a = &input_string[suffix_array[i - 1] as usize..];
+            let b = &input_string[suffix_array[i] as usize..];
+            let naive = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+            assert_eq!(lcp[i] as usize, naive);
+        }
+        let suffix_array_64 = crate::divsufsort64(input_string).unwrap();
+        let lcp_64 = lcp_array(input_string, &suffix_array_64);
+        assert!(lcp.iter().zip(&lcp_64).all(|(&a, &b)| a as i64 == b));
+    }
+}
@@ -4,12 +4,76 @@
 //! Including **all APIs** of both **32- and 64-bit** version.
 //! - More details are in included in original `C` codes of [`libdivsufsort`](https://github.com/y-256/libdivsufsort)  
 //! - I referred to [`pzip-bwt`](https://crates.io/crates/pzip-bwt) crate, which is more simpler version for wrapping around bwt function of `libdivsufsort`
+//!
+//! ## Features
+//! - `std` (default): implies `alloc`. Without it, the crate is `no_std`.
+//! - `alloc`: functions returning `Vec` or using temporary buffers.
+//! - `parallel`: parallel batch search with `rayon`.
+//! - `cli`: builds `mksary`, `sasearch`, `suftest`, `bwt` and `unbwt`, the example programs of `libdivsufsort`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 mod divsufsort;
 mod divsufsort64;
+mod sa;
+#[cfg(feature = "alloc")]
+mod rmq;
+#[cfg(feature = "alloc")]
+mod sais;
+#[cfg(test)]
+pub(crate) mod test_util;
+
+#[cfg(feature = "alloc")]
+pub mod approx;
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(feature = "alloc")]
+pub mod case_insensitive;
+#[cfg(feature = "alloc")]
+pub mod check;
+#[cfg(feature = "alloc")]
+pub mod common_substring;
+#[cfg(feature = "alloc")]
+pub mod dedup;
+#[cfg(feature = "alloc")]
+pub mod delta;
+#[cfg(feature = "alloc")]
+pub mod dna;
+#[cfg(feature = "alloc")]
+pub mod esa;
+#[cfg(feature = "std")]
+pub mod fasta;
+#[cfg(feature = "alloc")]
+pub mod integer;
+#[cfg(feature = "alloc")]
+pub mod iupac;
+#[cfg(feature = "alloc")]
+pub mod kmer;
+#[cfg(feature = "alloc")]
+pub mod lce;
+#[cfg(feature = "alloc")]
+pub mod lcp;
+#[cfg(feature = "alloc")]
+pub mod lz77;
+#[cfg(feature = "alloc")]
+pub mod packed;
+#[cfg(feature = "alloc")]
+pub mod packed_dna;
+pub mod search;
+#[cfg(feature = "alloc")]
+pub mod substring;
+#[cfg(feature = "alloc")]
+pub mod suffix_tree;
+
+pub use sa::{SaIndex, SuffixArray, Symbol};
 
-use std::ffi::CStr;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
 
+#[cfg(feature = "alloc")]
 #[inline]
 /// "Constructs the suffix array of a given string."  
 /// Input: Vector of bytes  
@@ -30,6 +94,7 @@ pub fn divsufsort(input_string: &[u8]) -> Option<Vec<i32>> {
         None
     }
 }
+#[cfg(feature = "alloc")]
 #[inline]
 /// 64-bit version of [divsufsort]
 pub fn divsufsort64(input_string: &[u8]) -> Option<Vec<i64>> {
@@ -49,6 +114,34 @@ pub fn divsufsort64(input_string: &[u8]) -> Option<Vec<i64>> {
     }
 }
 
+#[cfg(feature = "alloc")]
+/// Unsigned 32-bit version of [divsufsort] for texts up to `u32::MAX` bytes  
+/// Texts longer than `i32::MAX` are sorted by SA-IS written in Rust.
+pub fn divsufsort_u32(input_string: &[u8]) -> Option<Vec<u32>> {
+    let string_length = input_string.len();
+    if string_length > u32::MAX as usize {
+        return None;
+    }
+    let mut sa: Vec<u32> = vec![0; string_length];
+    if string_length <= i32::MAX as usize {
+        // Entries are in `0..i32::MAX`, so `i32` and `u32` have the same representation
+        let err = unsafe {
+            divsufsort::divsufsort(
+                input_string.as_ptr(),
+                sa.as_mut_ptr() as *mut i32,
+                string_length as i32,
+            )
+        };
+        if err != 0 {
+            return None;
+        }
+    } else {
+        sais::sais(|i: usize| input_string[i] as usize, 256, &mut sa);
+    }
+    Some(sa)
+}
+
+#[cfg(feature = "alloc")]
 #[inline]
 /// "Constructs the burrows-wheeler transformed string of a given string."  
 /// Input: Vector of bytes  
@@ -71,6 +164,7 @@ pub fn divbwt(input_string: &mut [u8]) -> Option<i32> {
         None
     }
 }
+#[cfg(feature = "alloc")]
 #[inline]
 /// 64-bit version of [divbwt]
 pub fn divbwt64(input_string: &mut [u8]) -> Option<i64> {
@@ -93,21 +187,22 @@ pub fn divbwt64(input_string: &mut [u8]) -> Option<i64> {
 
 #[inline]
 /// "Returns the version of the divsufsort library."
-pub fn divsufsort_version() -> String {
-    unsafe {
-        let ptr = divsufsort::divsufsort_version();
-        let cstr = CStr::from_ptr(ptr).to_str();
-        cstr.unwrap().to_string()
-    }
+pub fn divsufsort_version() -> &'static str {
+    unsafe { static_str_from_ptr(divsufsort::divsufsort_version()) }
 }
 #[inline]
 /// 64-bit version of [divsufsort_version]
-pub fn divsufsort64_version() -> String {
-    unsafe {
-        let ptr = divsufsort64::divsufsort64_version();
-        let cstr = CStr::from_ptr(ptr).to_str();
-        cstr.unwrap().to_string()
+pub fn divsufsort64_version() -> &'static str {
+    unsafe { static_str_from_ptr(divsufsort64::divsufsort64_version()) }
+}
+
+/// Reads a nul-terminated static string returned by the C library.
+unsafe fn static_str_from_ptr(ptr: *const u8) -> &'static str {
+    let mut length = 0;
+    while *ptr.add(length) != 0 {
+        length += 1;
     }
+    core::str::from_utf8(core::slice::from_raw_parts(ptr, length)).unwrap()
 }
 
 #[inline]
@@ -154,6 +249,7 @@ pub fn bw_transform64(input_string: &mut [u8], suffix_array: &mut [i64]) -> Opti
     }
 }
 
+#[cfg(feature = "alloc")]
 #[inline]
 /// "Inverse BW-transforms a given BWTed string."  
 /// Input: Vector of bytes(burrows wheeler transformed) & its primary index  
@@ -177,6 +273,7 @@ pub fn inverse_bw_transform(input_string: &mut [u8], primary_index: i32) -> Opti
         None
     }
 }
+#[cfg(feature = "alloc")]
 #[inline]
 /// 64-bit version of [inverse_bw_transform]
 pub fn inverse_bw_transform64(input_string: &mut [u8], primary_index: i64) -> Option<()> {
@@ -236,6 +333,23 @@ pub fn sufcheck64(input_string: &[u8], suffix_array: &[i64], verbose: bool) -> O
     }
 }
 
+#[cfg(feature = "alloc")]
+/// Unsigned 32-bit version of [sufcheck]  
+/// The check is written in Rust. See [check::verify] for the details of a failure.
+pub fn sufcheck_u32(input_string: &[u8], suffix_array: &[u32], verbose: bool) -> Option<()> {
+    let result = check::verify(input_string, suffix_array);
+    #[cfg(feature = "std")]
+    if verbose {
+        match &result {
+            Ok(()) => std::eprintln!("sufcheck: Done."),
+            Err(err) => std::eprintln!("sufcheck: {}", err),
+        }
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = verbose;
+    result.ok()
+}
+
 #[inline]
 // FIXME: index can receive multiple positions
 /// "Search for the pattern P in the string T."  
@@ -287,6 +401,15 @@ pub fn sa_search64(
     }
 }
 
+/// Unsigned 32-bit version of [sa_search]
+pub fn sa_search_u32(input_string: &[u8], pattern: &[u8], suffix_array: &[u32]) -> Option<(u32, u32)> {
+    if suffix_array.len() > input_string.len() {
+        return None;
+    }
+    let (lo, hi) = search::equal_range(input_string, pattern, suffix_array);
+    Some((lo as u32, (hi - lo) as u32))
+}
+
 #[inline]
 /// "Search for the character c in the string T."  
 /// Input: Vector of bytes & its suffix array and `i32` encoded character.  
@@ -337,11 +460,19 @@ pub fn sa_simplesearch64(input_string: &[u8], suffix_array: &[i64], character: i
     }
 }
 
-#[cfg(test)]
+/// Unsigned 32-bit version of [sa_simplesearch]
+pub fn sa_simplesearch_u32(input_string: &[u8], suffix_array: &[u32], character: i32) -> Option<(u32, u32)> {
+    if !(0..=255).contains(&character) {
+        return None;
+    }
+    sa_search_u32(input_string, &[character as u8], suffix_array)
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
-    fn vec_i32_to_i64(i32_vec: &Vec<i32>) -> Vec<i64> {
+    fn vec_i32_to_i64(i32_vec: &[i32]) -> Vec<i64> {
         i32_vec.iter().map(|&e| e as i64).collect::<Vec<i64>>()
     }
 
@@ -356,6 +487,19 @@ mod tests {
         assert_eq!(suffix_array_64, ans_64);
     }
 
+    #[test]
+    fn test_divsufsort_u32() {
+        let input_string = "CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC".as_bytes().to_vec();
+        let suffix_array = divsufsort(&input_string).unwrap();
+        let suffix_array_u32 = divsufsort_u32(&input_string).unwrap();
+        assert!(suffix_array.iter().zip(&suffix_array_u32).all(|(&a, &b)| a as u32 == b));
+
+        // Path for texts longer than `i32::MAX`
+        let mut sais_u32 = vec![0u32; input_string.len()];
+        sais::sais(|i: usize| input_string[i] as usize, 256, &mut sais_u32);
+        assert_eq!(sais_u32, suffix_array_u32);
+    }
+
     #[test]
     fn test_divbwt() {
         let input_string = "CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC".as_bytes().to_vec();
@@ -373,6 +517,12 @@ mod tests {
         assert_eq!((pidx_64, cloned_input), ans_64);
     }
     
+    #[test]
+    fn test_divsufsort_version() {
+        assert!(!divsufsort_version().is_empty());
+        assert_eq!(divsufsort_version(), divsufsort64_version());
+    }
+
     #[allow(dead_code)]
     fn print_divsufsort_version() {
         // 2.0.1-14-g5f60d6f
@@ -453,6 +603,15 @@ mod tests {
             sufcheck64(&input_string, &suffix_array, false)
         };
         assert_eq!(err, Some(()));
+
+        // u32
+        let mut suffix_array = divsufsort_u32(&input_string).unwrap();
+        assert_eq!(sufcheck_u32(&input_string, &suffix_array, false), Some(()));
+        suffix_array.swap(10, 11);
+        assert_eq!(sufcheck_u32(&input_string, &suffix_array, false), None);
+        suffix_array.swap(10, 11);
+        suffix_array[0] = suffix_array[1];
+        assert_eq!(sufcheck_u32(&input_string, &suffix_array, false), None);
     }
 
     #[test]
@@ -475,6 +634,14 @@ mod tests {
             (suffix_array[idx as usize], count)
         };
         assert_eq!((position, count), (5, 1));
+
+        // u32
+        let (position, count) = {
+            let suffix_array = divsufsort_u32(&input_string).unwrap();
+            let (idx, count) = sa_search_u32(&input_string, &pattern, &suffix_array).unwrap();
+            (suffix_array[idx as usize], count)
+        };
+        assert_eq!((position, count), (5, 1));
     }
 
     #[test]
@@ -497,5 +664,13 @@ mod tests {
             count
         };
         assert_eq!(count, 54);
+
+        // u32
+        let count = {
+            let suffix_array = divsufsort_u32(&input_string).unwrap();
+            let (_, count) = sa_simplesearch_u32(&input_string, &suffix_array, character).unwrap();
+            count
+        };
+        assert_eq!(count, 54);
     }
 }
\ No newline at end of file
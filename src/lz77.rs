@@ -0,0 +1,190 @@
+//! Longest previous factors (LPF) and the LZ77 factorization.
+//!
+//! The longest previous occurrence of the suffix at `i` starts at the nearest suffix with a smaller
+//! text position before or after `i` in the suffix array (Crochemore & Ilie 2008, Kärkkäinen, Kempa & Puglisi 2013).
+//! Both neighbours and their LCPs are found in one stack sweep over the suffix array in each direction.
+
+use alloc::{vec, vec::Vec};
+
+use crate::sa::SaIndex;
+
+/// Longest previous factor of every suffix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LongestPreviousFactors<I> {
+    /// `lengths[i]` is the length of the longest prefix of the suffix at `i` that also starts before `i`.
+    pub lengths: Vec<I>,
+    /// `sources[i] < i` is a start of that prefix, `EMPTY` (e.g. -1) if the length is 0.  
+    /// The occurrence may overlap position `i`.
+    pub sources: Vec<I>,
+}
+
+/// Computes the longest previous factors in `O(n)`.  
+/// Input: Suffix array & LCP array of the text (see [lcp_array](crate::lcp::lcp_array)).
+pub fn lpf_array<I: SaIndex>(suffix_array: &[I], lcp: &[I]) -> LongestPreviousFactors<I> {
+    assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+    let n = suffix_array.len();
+    let mut lengths = vec![I::from_usize(0); n];
+    let mut sources = vec![I::EMPTY; n];
+
+    // Stack entries are (rank, LCP with the entry below), with increasing text positions
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for rank in 0..n {
+        let position = suffix_array[rank];
+        let mut common = if rank == 0 { 0 } else { lcp[rank].to_usize() };
+        while let Some(&(top, top_common)) = stack.last() {
+            if suffix_array[top] < position {
+                break;
+            }
+            common = common.min(top_common);
+            stack.pop();
+        }
+        match stack.last() {
+            Some(&(top, _)) if common > 0 => {
+                lengths[position.to_usize()] = I::from_usize(common);
+                sources[position.to_usize()] = suffix_array[top];
+            }
+            _ => common = 0,
+        }
+        stack.push((rank, common));
+    }
+
+    stack.clear();
+    for rank in (0..n).rev() {
+        let position = suffix_array[rank];
+        let mut common = if rank + 1 == n { 0 } else { lcp[rank + 1].to_usize() };
+        while let Some(&(top, top_common)) = stack.last() {
+            if suffix_array[top] < position {
+                break;
+            }
+            common = common.min(top_common);
+            stack.pop();
+        }
+        match stack.last() {
+            Some(&(top, _)) if common > 0 => {
+                if common > lengths[position.to_usize()].to_usize() {
+                    lengths[position.to_usize()] = I::from_usize(common);
+                    sources[position.to_usize()] = suffix_array[top];
+                }
+            }
+            _ => common = 0,
+        }
+        stack.push((rank, common));
+    }
+    LongestPreviousFactors { lengths, sources }
+}
+
+/// A phrase of the LZ77 factorization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Phrase<T = u8> {
+    /// A symbol that does not occur before.
+    Literal(T),
+    /// A copy of `length` symbols from `source`, which may overlap the phrase itself.
+    Copy { source: usize, length: usize },
+}
+
+/// Computes the LZ77 factorization: each phrase is the longest previous factor at its start, or a literal.  
+/// Input: Text and its longest previous factors (see [lpf_array]).  
+/// Output: Phrases in text order. The number of phrases is a measure of the repetitiveness of the text.
+pub fn factorize<T: Copy, I: SaIndex>(input_string: &[T], lpf: &LongestPreviousFactors<I>) -> Vec<Phrase<T>> {
+    assert_eq!(input_string.len(), lpf.lengths.len(), "lengths of the text and the LPF array are different");
+    let mut phrases = Vec::new();
+    let mut i = 0;
+    while i < input_string.len() {
+        let length = lpf.lengths[i].to_usize();
+        if length == 0 {
+            phrases.push(Phrase::Literal(input_string[i]));
+            i += 1;
+        } else {
+            phrases.push(Phrase::Copy { source: lpf.sources[i].to_usize(), length });
+            i += length;
+        }
+    }
+    phrases
+}
+
+/// Computes the LZ77 factorization of a byte string with [divsufsort](crate::divsufsort).  
+/// Output: Phrases in text order, `None` if the suffix array cannot be constructed.
+/// ```rust
+/// use libdivsufsort_rs::lz77::{factorize_bytes, Phrase};
+///
+/// let phrases = factorize_bytes(b"abababab").unwrap();
+/// assert_eq!(phrases, [Phrase::Literal(b'a'), Phrase::Literal(b'b'), Phrase::Copy { source: 0, length: 6 }]);
+/// ```
+pub fn factorize_bytes(input_string: &[u8]) -> Option<Vec<Phrase<u8>>> {
+    let suffix_array = crate::divsufsort(input_string)?;
+    let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+    Some(factorize(input_string, &lpf_array(&suffix_array, &lcp)))
+}
+
+/// Reconstructs the text of an LZ77 factorization.  
+/// Output: The text, `None` if a phrase copies from a position that is not before it.
+pub fn decode<T: Copy>(phrases: &[Phrase<T>]) -> Option<Vec<T>> {
+    let mut text = Vec::new();
+    for phrase in phrases {
+        match *phrase {
+            Phrase::Literal(symbol) => text.push(symbol),
+            Phrase::Copy { source, length } => {
+                if source >= text.len() {
+                    return None;
+                }
+                // Symbol by symbol, as the copy may overlap itself
+                for i in source..source + length {
+                    text.push(text[i]);
+                }
+            }
+        }
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGG";
+
+    fn naive_lpf(input_string: &[u8]) -> Vec<usize> {
+        (0..input_string.len())
+            .map(|i| {
+                (0..i)
+                    .map(|j| input_string[j..].iter().zip(&input_string[i..]).take_while(|(a, b)| a == b).count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lpf_array() {
+        for input_string in [INPUT, b"AAAAAA", b"ABABABA", b"mississippi", b"A", b""] {
+            let suffix_array = crate::divsufsort64(input_string).unwrap();
+            let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+            let lpf = lpf_array(&suffix_array, &lcp);
+            let lengths: Vec<usize> = lpf.lengths.iter().map(|&length| length as usize).collect();
+            assert_eq!(lengths, naive_lpf(input_string));
+            for (i, (&length, &source)) in lpf.lengths.iter().zip(&lpf.sources).enumerate() {
+                if length == 0 {
+                    assert_eq!(source, -1);
+                } else {
+                    let (length, source) = (length as usize, source as usize);
+                    assert!(source < i);
+                    assert_eq!(input_string[source..source + length], input_string[i..i + length]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        for input_string in [INPUT, b"AAAAAA", b"mississippi", b""] {
+            let phrases = factorize_bytes(input_string).unwrap();
+            assert_eq!(decode(&phrases).unwrap(), input_string);
+        }
+        let phrases = factorize_bytes(b"AAAAAA").unwrap();
+        assert_eq!(phrases, [Phrase::Literal(b'A'), Phrase::Copy { source: 0, length: 5 }]);
+        // m, i, s, s, issi, p, p, i
+        assert_eq!(factorize_bytes(b"mississippi").unwrap().len(), 8);
+
+        assert_eq!(decode::<u8>(&[Phrase::Copy { source: 0, length: 1 }]), None);
+    }
+}
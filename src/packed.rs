@@ -0,0 +1,200 @@
+//! Bit-packed suffix arrays for large texts.
+//!
+//! Entries of the suffix array from [divsufsort64](crate::divsufsort64) are stored with a fixed bit width,
+//! 40 bits (texts up to 1 TiB) or `ceil(log2 n)` bits, instead of 64 bits.
+
+use alloc::vec::Vec;
+use core::mem::ManuallyDrop;
+
+use crate::sa::SuffixArray;
+use crate::search::equal_range;
+
+/// Suffix array whose entries are packed with a fixed bit width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedSuffixArray {
+    words: Vec<u64>,
+    width: u32,
+    len: usize,
+}
+
+impl PackedSuffixArray {
+    /// Packs a suffix array with `width` bits per entry.  
+    /// The vector is packed in place, so the original and the packed suffix arrays are never held together.  
+    /// Output: `None` if `width` is not in `1..=64` or an entry does not fit in `width` bits.
+    pub fn new(suffix_array: Vec<i64>, width: u32) -> Option<Self> {
+        if width == 0 || width > 64 {
+            return None;
+        }
+        let max_value = Self::mask(width);
+        if suffix_array.iter().any(|&position| position < 0 || position as u64 > max_value) {
+            return None;
+        }
+        let len = suffix_array.len();
+
+        // Reinterpret `Vec<i64>` as `Vec<u64>`: both have the same size and alignment
+        let mut words = {
+            let mut suffix_array = ManuallyDrop::new(suffix_array);
+            let (ptr, length, capacity) = (suffix_array.as_mut_ptr(), suffix_array.len(), suffix_array.capacity());
+            unsafe { Vec::from_raw_parts(ptr as *mut u64, length, capacity) }
+        };
+        // The word written after reading entry `i` is at most `i`, so no unread entry is overwritten.
+        let mut buffer: u128 = 0;
+        let mut buffered_bits = 0;
+        let mut word_count = 0;
+        for i in 0..len {
+            buffer |= (words[i] as u128) << buffered_bits;
+            buffered_bits += width;
+            while buffered_bits >= 64 {
+                words[word_count] = buffer as u64;
+                word_count += 1;
+                buffer >>= 64;
+                buffered_bits -= 64;
+            }
+        }
+        if buffered_bits > 0 {
+            words[word_count] = buffer as u64;
+            word_count += 1;
+        }
+        words.truncate(word_count);
+        words.shrink_to_fit();
+
+        Some(Self { words, width, len })
+    }
+    /// Packs a suffix array with 40 bits per entry (texts up to 1 TiB).
+    pub fn new_40bit(suffix_array: Vec<i64>) -> Option<Self> {
+        Self::new(suffix_array, 40)
+    }
+    /// Packs a suffix array with the minimal bits per entry, `ceil(log2 n)`.
+    pub fn new_compact(suffix_array: Vec<i64>) -> Self {
+        let width = Self::minimal_width(suffix_array.len());
+        Self::new(suffix_array, width).unwrap()
+    }
+    /// Constructs the suffix array with [divsufsort64](crate::divsufsort64) and packs it with the minimal bits per entry.
+    pub fn from_text(input_string: &[u8]) -> Option<Self> {
+        crate::divsufsort64(input_string).map(Self::new_compact)
+    }
+
+    /// Bits per entry for a text of `length`, `ceil(log2 length)` (at least 1).
+    pub fn minimal_width(length: usize) -> u32 {
+        match length {
+            0..=2 => 1,
+            _ => usize::BITS - (length - 1).leading_zeros(),
+        }
+    }
+    #[inline]
+    fn mask(width: u32) -> u64 {
+        if width == 64 {
+            u64::MAX
+        } else {
+            (1 << width) - 1
+        }
+    }
+
+    /// Bits per entry.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    /// Number of entries, the length of the text.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether the suffix array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Size of the packed entries in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    /// Text position of the suffix at `rank`.
+    #[inline]
+    pub fn get(&self, rank: usize) -> u64 {
+        assert!(rank < self.len, "rank {} is out of range for length {}", rank, self.len);
+        let bit = rank * self.width as usize;
+        let (index, offset) = (bit / 64, (bit % 64) as u32);
+        let mut value = self.words[index] >> offset;
+        if offset + self.width > 64 {
+            value |= self.words[index + 1] << (64 - offset);
+        }
+        value & Self::mask(self.width)
+    }
+    /// Iterates the entries in rank order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).map(move |rank| self.get(rank))
+    }
+    /// Unpacks to the suffix array of [divsufsort64](crate::divsufsort64).
+    pub fn to_vec(&self) -> Vec<i64> {
+        self.iter().map(|position| position as i64).collect()
+    }
+
+    /// "Search for the pattern P in the string T."  
+    /// Input: Vector of bytes and vector of pattern string.  
+    /// Output: tuple of index of suffix array for matched pattern and pattern count
+    pub fn sa_search(&self, input_string: &[u8], pattern: &[u8]) -> Option<(usize, usize)> {
+        if self.len > input_string.len() {
+            return None;
+        }
+        let (lo, hi) = equal_range(input_string, pattern, self);
+        Some((lo, hi - lo))
+    }
+    /// Text positions of `count` suffixes from `idx`, e.g. the result of [sa_search](Self::sa_search).
+    pub fn locate(&self, idx: usize, count: usize) -> impl Iterator<Item = u64> + '_ {
+        (idx..idx + count).map(move |rank| self.get(rank))
+    }
+}
+
+impl SuffixArray for PackedSuffixArray {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn position(&self, rank: usize) -> usize {
+        self.get(rank) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    #[test]
+    fn test_packing() {
+        let suffix_array = crate::divsufsort64(INPUT).unwrap();
+        for width in [8, 13, 40, 63, 64] {
+            let packed = PackedSuffixArray::new(suffix_array.clone(), width).unwrap();
+            assert_eq!(packed.to_vec(), suffix_array);
+            assert_eq!(packed.size_in_bytes(), (suffix_array.len() * width as usize + 63) / 64 * 8);
+        }
+        assert_eq!(PackedSuffixArray::new(suffix_array.clone(), 7), None);
+
+        let compact = PackedSuffixArray::new_compact(suffix_array.clone());
+        assert_eq!(compact.width(), 8);
+        assert_eq!(compact.to_vec(), suffix_array);
+        assert_eq!(PackedSuffixArray::from_text(INPUT), Some(compact));
+        assert_eq!(PackedSuffixArray::minimal_width(256), 8);
+        assert_eq!(PackedSuffixArray::minimal_width(257), 9);
+    }
+
+    #[test]
+    fn test_sa_search() {
+        let suffix_array = crate::divsufsort64(INPUT).unwrap();
+        let packed = PackedSuffixArray::new_40bit(suffix_array.clone()).unwrap();
+        for pattern in [&b"TACACCTGTTTCG"[..], b"CG", b"GGG", b"AAAA"] {
+            let (idx, count) = crate::sa_search64(INPUT, pattern, &suffix_array).unwrap();
+            let (packed_idx, packed_count) = packed.sa_search(INPUT, pattern).unwrap();
+            assert_eq!(packed_count as i64, count);
+            if count == 0 {
+                continue;
+            }
+            let mut positions: Vec<u64> = packed.locate(packed_idx, packed_count).collect();
+            let mut expected: Vec<u64> = suffix_array[idx as usize..(idx + count) as usize].iter().map(|&p| p as u64).collect();
+            positions.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(positions, expected);
+        }
+    }
+}
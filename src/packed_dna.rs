@@ -0,0 +1,230 @@
+//! 2-bit packed nucleotide texts.
+//!
+//! Bases are stored in 2 bits (`A`, `C`, `G`, `T`) with a side list of the runs of `N`,
+//! so a genome takes a quarter of its size in bytes. The suffix array is constructed by SA-IS
+//! reading the bases from the packed words, without unpacking the whole text,
+//! and is the same as the one of [divsufsort64](crate::divsufsort64) on the unpacked text.
+
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::ops::Range;
+
+use crate::sa::SuffixArray;
+use crate::sais::sais;
+use crate::search::{partition_point, Positions};
+
+const BASES: [u8; 4] = *b"ACGT";
+const BASES_PER_WORD: usize = 32;
+
+/// Nucleotide text packed in 2 bits per base with runs of `N`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedDna {
+    /// Base `i` is in bits `2 * (i % 32)..2 * (i % 32) + 2` of `words[i / 32]`: `A`=0, `C`=1, `G`=2 and `T`=3.
+    words: Vec<u64>,
+    len: usize,
+    /// Sorted and disjoint runs of `N`, whose bits in `words` are ignored.
+    n_runs: Vec<Range<usize>>,
+}
+
+impl PackedDna {
+    /// Takes an already packed text of `len` bases.  
+    /// Output: `None` if `words` is too short or `n_runs` are not sorted, disjoint, non-empty and in `0..len`.
+    pub fn new(words: Vec<u64>, len: usize, n_runs: Vec<Range<usize>>) -> Option<Self> {
+        if words.len() < (len + BASES_PER_WORD - 1) / BASES_PER_WORD {
+            return None;
+        }
+        let is_valid_run = |run: &Range<usize>| run.start < run.end && run.end <= len;
+        if !n_runs.iter().all(is_valid_run) || !n_runs.windows(2).all(|w| w[0].end <= w[1].start) {
+            return None;
+        }
+        Some(Self { words, len, n_runs })
+    }
+    /// Packs bases in upper or lower case. Symbols other than `ACGT` are packed as `N`.
+    pub fn from_bases(bases: &[u8]) -> Self {
+        let mut words = vec![0u64; (bases.len() + BASES_PER_WORD - 1) / BASES_PER_WORD];
+        let mut n_runs: Vec<Range<usize>> = Vec::new();
+        for (i, &base) in bases.iter().enumerate() {
+            let code = match base.to_ascii_uppercase() {
+                b'A' => 0,
+                b'C' => 1,
+                b'G' => 2,
+                b'T' => 3,
+                _ => {
+                    match n_runs.last_mut() {
+                        Some(run) if run.end == i => run.end += 1,
+                        _ => n_runs.push(i..i + 1),
+                    }
+                    continue;
+                }
+            };
+            words[i / BASES_PER_WORD] |= code << (2 * (i % BASES_PER_WORD));
+        }
+        Self { words, len: bases.len(), n_runs }
+    }
+
+    /// Number of bases.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether there are no bases.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Packed bases.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+    /// Runs of `N`.
+    pub fn n_runs(&self) -> &[Range<usize>] {
+        &self.n_runs
+    }
+    /// Size of the packed bases and the runs of `N` in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.len() * 8 + self.n_runs.len() * core::mem::size_of::<Range<usize>>()
+    }
+
+    #[inline]
+    fn is_n(&self, i: usize) -> bool {
+        if self.n_runs.is_empty() {
+            return false;
+        }
+        let index = self.n_runs.partition_point(|run| run.end <= i);
+        index < self.n_runs.len() && self.n_runs[index].start <= i
+    }
+    #[inline]
+    fn code(&self, i: usize) -> usize {
+        (self.words[i / BASES_PER_WORD] >> (2 * (i % BASES_PER_WORD)) & 3) as usize
+    }
+    /// Rank of the base in byte order: `A`=0, `C`=1, `G`=2, `N`=3 and `T`=4.
+    #[inline]
+    fn rank(&self, i: usize) -> usize {
+        if self.is_n(i) {
+            3
+        } else {
+            [0, 1, 2, 4][self.code(i)]
+        }
+    }
+    /// Base at `i` in upper case.
+    #[inline]
+    pub fn get(&self, i: usize) -> u8 {
+        assert!(i < self.len, "index {} is out of range for length {}", i, self.len);
+        if self.is_n(i) {
+            b'N'
+        } else {
+            BASES[self.code(i)]
+        }
+    }
+    /// Unpacks the bases in `range`, e.g. a chunk of a large text.
+    pub fn unpack(&self, range: Range<usize>) -> Vec<u8> {
+        assert!(range.end <= self.len, "range end {} is out of range for length {}", range.end, self.len);
+        let mut bases: Vec<u8> = range.clone().map(|i| BASES[self.code(i)]).collect();
+        for run in &self.n_runs {
+            let (start, end) = (run.start.max(range.start), run.end.min(range.end));
+            if start < end {
+                bases[start - range.start..end - range.start].fill(b'N');
+            }
+        }
+        bases
+    }
+    /// Unpacks the whole text.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.unpack(0..self.len)
+    }
+
+    /// Constructs the suffix array reading the packed bases.  
+    /// Output: The suffix array of [divsufsort64](crate::divsufsort64) on the unpacked text.
+    pub fn suffix_array(&self) -> Vec<i64> {
+        let mut suffix_array = vec![0i64; self.len];
+        sais(|i: usize| self.rank(i), 5, &mut suffix_array);
+        suffix_array
+    }
+
+    /// Compares the suffix at `position` with `pattern`, treating the suffix as equal if it starts with `pattern`.
+    fn compare_prefix(&self, position: usize, pattern: &PackedDna) -> Ordering {
+        let length = (self.len - position).min(pattern.len);
+        for i in 0..length {
+            match self.rank(position + i).cmp(&pattern.rank(i)) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+        if length == pattern.len {
+            Ordering::Equal
+        } else {
+            Ordering::Less
+        }
+    }
+    /// Search for the packed pattern P in the packed text T.  
+    /// Input: Packed pattern and the suffix array of the text. `N` in the pattern matches `N` in the text.  
+    /// Output: Text positions of the matches, in lexicographic order of the suffixes.  
+    /// The range of suffix array indices is given by [Positions::ranks].
+    /// ```rust
+    /// use libdivsufsort_rs::packed_dna::PackedDna;
+    ///
+    /// let text = PackedDna::from_bases(b"ACGTNNACGT");
+    /// let suffix_array = text.suffix_array();
+    /// let mut positions: Vec<usize> = text.search(&PackedDna::from_bases(b"acg"), &suffix_array).collect();
+    /// positions.sort();
+    /// assert_eq!(positions, vec![0, 6]);
+    /// ```
+    pub fn search<'a, S: SuffixArray + ?Sized>(&self, pattern: &PackedDna, suffix_array: &'a S) -> Positions<'a, S> {
+        let n = suffix_array.len();
+        let lo = partition_point(0, n, |rank| {
+            self.compare_prefix(suffix_array.position(rank), pattern) == Ordering::Less
+        });
+        let hi = partition_point(lo, n, |rank| {
+            self.compare_prefix(suffix_array.position(rank), pattern) != Ordering::Greater
+        });
+        Positions::new(suffix_array, lo..hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    fn sequence() -> Vec<u8> {
+        let mut sequence = INPUT.to_vec();
+        sequence[10..20].fill(b'N');
+        sequence[20] = b'n';
+        sequence[64] = b'N';
+        sequence[100..120].make_ascii_lowercase();
+        sequence
+    }
+
+    #[test]
+    fn test_packing() {
+        let sequence = sequence();
+        let packed = PackedDna::from_bases(&sequence);
+        let upper = sequence.to_ascii_uppercase();
+        assert_eq!(packed.to_bytes(), upper);
+        assert_eq!(packed.n_runs(), &[10..21, 64..65]);
+        assert_eq!(packed.unpack(5..70), &upper[5..70]);
+        assert_eq!(packed.get(64), b'N');
+        assert_eq!(packed.size_in_bytes(), 7 * 8 + 2 * core::mem::size_of::<Range<usize>>());
+
+        let words = packed.words().to_vec();
+        assert_eq!(PackedDna::new(words.clone(), packed.len(), packed.n_runs().to_vec()), Some(packed));
+        assert_eq!(PackedDna::new(words.clone(), 7 * 32 + 1, Vec::new()), None);
+        assert_eq!(PackedDna::new(words.clone(), INPUT.len(), vec![3..5, 4..6]), None);
+        assert_eq!(PackedDna::new(words, INPUT.len(), vec![3..5, 6..6]), None);
+    }
+
+    #[test]
+    fn test_suffix_array_and_search() {
+        let sequence = sequence().to_ascii_uppercase();
+        let packed = PackedDna::from_bases(&sequence);
+        let suffix_array = packed.suffix_array();
+        assert_eq!(suffix_array, crate::divsufsort64(&sequence).unwrap());
+
+        for pattern in [&b"CCGG"[..], b"NNNA", b"TTTAC", b"gaaac", b"GGGG", b"CTCCGTACAC", b"T"] {
+            let mut positions: Vec<usize> = packed.search(&PackedDna::from_bases(pattern), &suffix_array).collect();
+            positions.sort_unstable();
+            let pattern = pattern.to_ascii_uppercase();
+            let expected: Vec<usize> = (0..sequence.len()).filter(|&p| sequence[p..].starts_with(&pattern)).collect();
+            assert_eq!(positions, expected);
+        }
+    }
+}
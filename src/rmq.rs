@@ -0,0 +1,76 @@
+//! Range minimum queries with a sparse table.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::sa::SaIndex;
+
+/// `O(1)` range minimum queries over an array of `n` values, with `n log n` indices.
+#[derive(Clone, Debug)]
+pub(crate) struct SparseTable<I> {
+    /// `levels[k][i]` is the index of the minimum in `i..i + 2^(k + 1)`.
+    levels: Vec<Vec<I>>,
+}
+
+#[inline]
+fn floor_log2(value: usize) -> usize {
+    (usize::BITS - 1 - value.leading_zeros()) as usize
+}
+
+impl<I: SaIndex> SparseTable<I> {
+    pub(crate) fn new(values: &[I]) -> Self {
+        let n = values.len();
+        let mut levels: Vec<Vec<I>> = Vec::new();
+        let mut width = 1;
+        while 2 * width <= n {
+            let level: Vec<I> = (0..=n - 2 * width)
+                .map(|i| {
+                    let (left, right) = match levels.last() {
+                        Some(previous) => (previous[i].to_usize(), previous[i + width].to_usize()),
+                        None => (i, i + 1),
+                    };
+                    I::from_usize(if values[right] < values[left] { right } else { left })
+                })
+                .collect();
+            levels.push(level);
+            width *= 2;
+        }
+        Self { levels }
+    }
+
+    /// Index of the leftmost minimum of `values[range]`, where `values` is the array of [new](Self::new).
+    #[inline]
+    pub(crate) fn argmin(&self, values: &[I], range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "empty range {:?}", range);
+        let length = range.end - range.start;
+        if length == 1 {
+            return range.start;
+        }
+        let k = floor_log2(length);
+        let level = &self.levels[k - 1];
+        let left = level[range.start].to_usize();
+        let right = level[range.end - (1 << k)].to_usize();
+        if values[right] < values[left] {
+            right
+        } else {
+            left
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argmin() {
+        let values: Vec<i32> = (0..100).map(|i| (i * 37 + 11) % 23).collect();
+        let table = SparseTable::new(&values);
+        for start in 0..values.len() {
+            for end in start + 1..=values.len() {
+                let expected = (start..end).min_by_key(|&i| values[i]).unwrap();
+                assert_eq!(table.argmin(&values, start..end), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Traits over the integer types and containers of suffix arrays.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+}
+
+/// Integer type of suffix array entries (`i32` for 32-bit, `i64` for 64-bit and `u32` for unsigned 32-bit version).
+pub trait SaIndex: Copy + Ord + private::Sealed {
+    /// Marks an unfilled slot during construction.
+    #[doc(hidden)]
+    const EMPTY: Self;
+    /// The longest text whose suffix array can be stored with this type.
+    const MAX_LENGTH: usize;
+
+    fn to_usize(self) -> usize;
+    fn from_usize(value: usize) -> Self;
+}
+
+impl SaIndex for i32 {
+    const EMPTY: Self = -1;
+    const MAX_LENGTH: usize = i32::MAX as usize;
+
+    #[inline]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    #[inline]
+    fn from_usize(value: usize) -> Self {
+        value as i32
+    }
+}
+
+impl SaIndex for i64 {
+    const EMPTY: Self = -1;
+    const MAX_LENGTH: usize = i64::MAX as usize;
+
+    #[inline]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    #[inline]
+    fn from_usize(value: usize) -> Self {
+        value as i64
+    }
+}
+
+impl SaIndex for u32 {
+    const EMPTY: Self = u32::MAX;
+    const MAX_LENGTH: usize = u32::MAX as usize;
+
+    #[inline]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    #[inline]
+    fn from_usize(value: usize) -> Self {
+        value as u32
+    }
+}
+
+/// Integer type of text symbols (`u8`, `u16` and `u32`).
+pub trait Symbol: Copy + Ord + private::Sealed {
+    /// Value of the symbol as an index of the alphabet.
+    fn rank(self) -> usize;
+}
+
+impl Symbol for u8 {
+    #[inline]
+    fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+impl Symbol for u16 {
+    #[inline]
+    fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+impl Symbol for u32 {
+    #[inline]
+    fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+/// Read access to a suffix array.
+/// Implemented for slices and vectors of [SaIndex].
+pub trait SuffixArray {
+    /// Number of suffixes.
+    fn len(&self) -> usize;
+    /// Text position of the suffix at `rank`.
+    fn position(&self, rank: usize) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<I: SaIndex> SuffixArray for [I] {
+    #[inline]
+    fn len(&self) -> usize {
+        <[I]>::len(self)
+    }
+    #[inline]
+    fn position(&self, rank: usize) -> usize {
+        self[rank].to_usize()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: SaIndex> SuffixArray for Vec<I> {
+    #[inline]
+    fn len(&self) -> usize {
+        <[I]>::len(self)
+    }
+    #[inline]
+    fn position(&self, rank: usize) -> usize {
+        self[rank].to_usize()
+    }
+}
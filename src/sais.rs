@@ -0,0 +1,273 @@
+//! Suffix array construction by induced sorting (SA-IS, Nong, Zhang & Chan 2009).
+//! Used for the texts `libdivsufsort` cannot take.
+
+use alloc::{vec, vec::Vec};
+
+use crate::sa::SaIndex;
+
+/// Symbols of the text being sorted.
+pub(crate) trait Text {
+    fn at(&self, i: usize) -> usize;
+}
+
+impl<F: Fn(usize) -> usize> Text for F {
+    #[inline]
+    fn at(&self, i: usize) -> usize {
+        self(i)
+    }
+}
+
+/// Reduced problem of the recursion, so that every level shares one instantiation.
+struct Reduced<'a, I>(&'a [I]);
+
+impl<I: SaIndex> Text for Reduced<'_, I> {
+    #[inline]
+    fn at(&self, i: usize) -> usize {
+        self.0[i].to_usize()
+    }
+}
+
+/// Bit vector of suffix types (`true` for S-type).
+struct Types(Vec<u64>);
+
+impl Types {
+    fn new<F: Text>(text: &F, n: usize) -> Self {
+        let mut bits = vec![0u64; (n + 63) / 64];
+        // The last suffix is L-type, as it is larger than the virtual sentinel
+        let mut next_is_s = false;
+        for i in (0..n.saturating_sub(1)).rev() {
+            let (c, next) = (text.at(i), text.at(i + 1));
+            let is_s = c < next || (c == next && next_is_s);
+            if is_s {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+            next_is_s = is_s;
+        }
+        Self(bits)
+    }
+    #[inline]
+    fn is_s(&self, i: usize) -> bool {
+        self.0[i / 64] >> (i % 64) & 1 == 1
+    }
+    #[inline]
+    fn is_lms(&self, i: usize) -> bool {
+        i > 0 && self.is_s(i) && !self.is_s(i - 1)
+    }
+}
+
+/// Counts the symbols into `buckets`, then stores the start of each bucket, or its end with `tails`.
+fn get_buckets<I: SaIndex, F: Text>(text: &F, n: usize, buckets: &mut [I], tails: bool) {
+    for bucket in buckets.iter_mut() {
+        *bucket = I::from_usize(0);
+    }
+    for i in 0..n {
+        let c = text.at(i);
+        buckets[c] = I::from_usize(buckets[c].to_usize() + 1);
+    }
+    let mut sum = 0;
+    for bucket in buckets.iter_mut() {
+        let size = bucket.to_usize();
+        sum += size;
+        *bucket = I::from_usize(if tails { sum } else { sum - size });
+    }
+}
+
+/// Puts `value` at the head of bucket `c` and advances the head.
+#[inline]
+fn push_head<I: SaIndex>(sa: &mut [I], buckets: &mut [I], c: usize, value: usize) {
+    let head = buckets[c].to_usize();
+    sa[head] = I::from_usize(value);
+    buckets[c] = I::from_usize(head + 1);
+}
+
+/// Moves the tail of bucket `c` back and puts `value` there.
+#[inline]
+fn push_tail<I: SaIndex>(sa: &mut [I], buckets: &mut [I], c: usize, value: usize) {
+    let tail = buckets[c].to_usize() - 1;
+    sa[tail] = I::from_usize(value);
+    buckets[c] = I::from_usize(tail);
+}
+
+fn induce<I: SaIndex, F: Text>(text: &F, types: &Types, buckets: &mut [I], sa: &mut [I]) {
+    let n = sa.len();
+    // L-type suffixes, from left to right
+    get_buckets(text, n, buckets, false);
+    push_head(sa, buckets, text.at(n - 1), n - 1);
+    for i in 0..n {
+        let j = sa[i];
+        if j != I::EMPTY && j.to_usize() > 0 && !types.is_s(j.to_usize() - 1) {
+            let j = j.to_usize() - 1;
+            push_head(sa, buckets, text.at(j), j);
+        }
+    }
+    // S-type suffixes, from right to left
+    get_buckets(text, n, buckets, true);
+    for i in (0..n).rev() {
+        let j = sa[i];
+        if j != I::EMPTY && j.to_usize() > 0 && types.is_s(j.to_usize() - 1) {
+            let j = j.to_usize() - 1;
+            push_tail(sa, buckets, text.at(j), j);
+        }
+    }
+}
+
+/// Whether the LMS substrings starting at `a` and `b` are equal.
+fn lms_substring_eq<F: Text>(text: &F, types: &Types, n: usize, a: usize, b: usize) -> bool {
+    let mut d = 0;
+    loop {
+        // Only one of them can reach the virtual sentinel
+        if a + d == n || b + d == n {
+            return false;
+        }
+        if text.at(a + d) != text.at(b + d) || types.is_s(a + d) != types.is_s(b + d) {
+            return false;
+        }
+        if d > 0 {
+            let (a_end, b_end) = (types.is_lms(a + d), types.is_lms(b + d));
+            if a_end || b_end {
+                return a_end && b_end;
+            }
+        }
+        d += 1;
+    }
+}
+
+/// Constructs the suffix array of `text` (of length `sa.len()`) whose symbols are in `0..alphabet_size`.  
+/// Extra space besides `sa`: the suffix types of every level (`n / 4` bytes in total) and, as in sais-lite,  
+/// the buckets of every level as `alphabet_size` entries of `I`, kept in the unused part of `sa` if they fit.  
+/// The reduced problem at level `i` has at most `n / 2^i` symbols, so at most `n` bucket entries are allocated.  
+/// On random bytes, the first level has about `n / 3` names, which do not fit.
+pub(crate) fn sais<I: SaIndex, F: Text>(text: F, alphabet_size: usize, sa: &mut [I]) {
+    sais_in(&text, alphabet_size, sa, &mut []);
+}
+
+/// [sais] with `free` as space for the buckets.
+fn sais_in<I: SaIndex, F: Text>(text: &F, alphabet_size: usize, sa: &mut [I], free: &mut [I]) {
+    let n = sa.len();
+    match n {
+        0 => return,
+        1 => {
+            sa[0] = I::from_usize(0);
+            return;
+        }
+        _ => {}
+    }
+    let types = Types::new(text, n);
+    let mut allocated: Vec<I>;
+    let buckets = if free.len() >= alphabet_size {
+        &mut free[..alphabet_size]
+    } else {
+        allocated = vec![I::from_usize(0); alphabet_size];
+        &mut allocated[..]
+    };
+
+    // Stage 1: sort LMS substrings
+    for slot in sa.iter_mut() {
+        *slot = I::EMPTY;
+    }
+    get_buckets(text, n, buckets, true);
+    for i in (1..n).rev() {
+        if types.is_lms(i) {
+            push_tail(sa, buckets, text.at(i), i);
+        }
+    }
+    induce(text, &types, buckets, sa);
+
+    // Compact the sorted LMS positions into the head of `sa`
+    let mut lms_count = 0;
+    for i in 0..n {
+        let j = sa[i];
+        if types.is_lms(j.to_usize()) {
+            sa[lms_count] = j;
+            lms_count += 1;
+        }
+    }
+    if lms_count == 0 {
+        // Non-increasing text: every suffix is L-type and already sorted
+        return;
+    }
+
+    // Name LMS substrings at `lms_count + position / 2`
+    for slot in sa[lms_count..].iter_mut() {
+        *slot = I::EMPTY;
+    }
+    let mut name_count = 0;
+    let mut previous = n;
+    for i in 0..lms_count {
+        let position = sa[i].to_usize();
+        if previous == n || !lms_substring_eq(text, &types, n, previous, position) {
+            name_count += 1;
+        }
+        previous = position;
+        sa[lms_count + position / 2] = I::from_usize(name_count - 1);
+    }
+    let mut j = n;
+    for i in (lms_count..n).rev() {
+        if sa[i] != I::EMPTY {
+            j -= 1;
+            sa[j] = sa[i];
+        }
+    }
+
+    // Stage 2: sort LMS suffixes by the reduced problem
+    {
+        let (sa1, rest) = sa.split_at_mut(lms_count);
+        // The names are at the end of `rest`, before them is free space for the buckets of the reduced problem
+        let (rest_free, s1) = rest.split_at_mut(rest.len() - lms_count);
+        if name_count < lms_count {
+            sais_in(&Reduced(s1), name_count, sa1, rest_free);
+        } else {
+            for (i, name) in s1.iter().enumerate() {
+                sa1[name.to_usize()] = I::from_usize(i);
+            }
+        }
+        // Map ranks of the reduced problem to LMS positions
+        let mut j = 0;
+        for i in 1..n {
+            if types.is_lms(i) {
+                s1[j] = I::from_usize(i);
+                j += 1;
+            }
+        }
+        for slot in sa1.iter_mut() {
+            *slot = s1[slot.to_usize()];
+        }
+    }
+
+    // Stage 3: induce the final suffix array from the sorted LMS suffixes
+    for slot in sa[lms_count..].iter_mut() {
+        *slot = I::EMPTY;
+    }
+    get_buckets(text, n, buckets, true);
+    for i in (0..lms_count).rev() {
+        let j = sa[i].to_usize();
+        sa[i] = I::EMPTY;
+        push_tail(sa, buckets, text.at(j), j);
+    }
+    induce(text, &types, buckets, sa);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Lcg;
+
+    fn naive_suffix_array(text: &[usize]) -> Vec<i64> {
+        let mut sa: Vec<i64> = (0..text.len() as i64).collect();
+        sa.sort_by(|&a, &b| text[a as usize..].cmp(&text[b as usize..]));
+        sa
+    }
+
+    #[test]
+    fn test_sais_against_naive_sort() {
+        let mut rng = Lcg::new(7);
+        for length in 0..200 {
+            for alphabet_size in [1, 2, 3, 4, 30] {
+                let text: Vec<usize> = (0..length).map(|_| rng.below(alphabet_size)).collect();
+                let mut sa = vec![0i64; length];
+                sais(|i: usize| text[i], alphabet_size, &mut sa);
+                assert_eq!(sa, naive_suffix_array(&text));
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Suffix array search written in Rust, for any [SuffixArray] of texts of any `Ord` symbols.
+
+use core::cmp::Ordering;
+use core::ops::{Bound, Range};
+
+use crate::sa::SuffixArray;
+
+/// Compares the suffix at `position` with `pattern`, treating the suffix as equal if it starts with `pattern`.
+#[inline]
+pub(crate) fn compare_prefix<T: Ord>(text: &[T], position: usize, pattern: &[T]) -> Ordering {
+    let suffix = &text[position..];
+    if suffix.len() >= pattern.len() {
+        suffix[..pattern.len()].cmp(pattern)
+    } else {
+        suffix.cmp(pattern)
+    }
+}
+
+/// Returns the first rank in `lo..hi` for which `is_left` is false.
+#[inline]
+pub(crate) fn partition_point<F: FnMut(usize) -> bool>(mut lo: usize, mut hi: usize, mut is_left: F) -> usize {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_left(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Returns the first rank whose suffix is not less than `pattern`.
+pub(crate) fn lower_bound<T: Ord, S: SuffixArray + ?Sized>(text: &[T], pattern: &[T], suffix_array: &S) -> usize {
+    partition_point(0, suffix_array.len(), |rank| {
+        compare_prefix(text, suffix_array.position(rank), pattern) == Ordering::Less
+    })
+}
+
+/// Returns the first rank from `lo` whose suffix is greater than `pattern` and does not start with it.
+pub(crate) fn upper_bound<T: Ord, S: SuffixArray + ?Sized>(
+    text: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+    lo: usize,
+) -> usize {
+    partition_point(lo, suffix_array.len(), |rank| {
+        compare_prefix(text, suffix_array.position(rank), pattern) != Ordering::Greater
+    })
+}
+
+/// Returns the half-open range of ranks whose suffixes start with `pattern`.
+/// If there is no such suffix, the range is empty and starts at the insertion point of `pattern`.
+pub(crate) fn equal_range<T: Ord, S: SuffixArray + ?Sized>(
+    text: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+) -> (usize, usize) {
+    let lo = lower_bound(text, pattern, suffix_array);
+    (lo, upper_bound(text, pattern, suffix_array, lo))
+}
+
+/// Search for the pattern P in the string T, with the insertion point when P is absent.  
+/// Input: Vector of symbols & vector of pattern string and its suffix array.  
+/// Output: `Ok` of the half-open range `[lo, hi)` of suffix array indices whose suffixes start with the pattern,
+/// or `Err` of the index where the pattern would be inserted keeping the suffix array sorted.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, search::sa_search_range};
+///
+/// let input_string = b"abracadabra";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// assert_eq!(sa_search_range(input_string, b"abra", &suffix_array), Ok(1..3));
+/// // "a" < "abc" < "abra"
+/// assert_eq!(sa_search_range(input_string, b"abc", &suffix_array), Err(1));
+/// ```
+pub fn sa_search_range<T: Ord, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+) -> Result<Range<usize>, usize> {
+    let (lo, hi) = equal_range(input_string, pattern, suffix_array);
+    if lo < hi {
+        Ok(lo..hi)
+    } else {
+        Err(lo)
+    }
+}
+
+/// Symbol of the suffix at `rank` after `depth` symbols, `None` if the suffix is not longer than `depth`.
+#[inline]
+pub(crate) fn symbol_at<'t, T, S: SuffixArray + ?Sized>(text: &'t [T], suffix_array: &S, rank: usize, depth: usize) -> Option<&'t T> {
+    text.get(suffix_array.position(rank) + depth)
+}
+
+/// Narrows `ranks`, whose suffixes share their first `depth` symbols,
+/// to the suffixes followed by `symbol` after them.
+#[inline]
+pub(crate) fn narrow<T: Ord, S: SuffixArray + ?Sized>(
+    text: &[T],
+    suffix_array: &S,
+    ranks: Range<usize>,
+    depth: usize,
+    symbol: &T,
+) -> Range<usize> {
+    let key = Some(symbol);
+    let lo = partition_point(ranks.start, ranks.end, |rank| symbol_at(text, suffix_array, rank, depth) < key);
+    let hi = partition_point(lo, ranks.end, |rank| symbol_at(text, suffix_array, rank, depth) <= key);
+    lo..hi
+}
+
+/// Splits `ranks`, whose suffixes share their first `depth` symbols, by the symbol following them.
+/// Suffixes of length `depth` are skipped.
+#[cfg(feature = "alloc")]
+pub(crate) struct Children<'a, T, S: ?Sized> {
+    text: &'a [T],
+    suffix_array: &'a S,
+    ranks: Range<usize>,
+    depth: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Ord, S: SuffixArray + ?Sized> Children<'a, T, S> {
+    pub(crate) fn new(text: &'a [T], suffix_array: &'a S, ranks: Range<usize>, depth: usize) -> Self {
+        // Shorter suffixes are sorted first
+        let start = partition_point(ranks.start, ranks.end, |rank| symbol_at(text, suffix_array, rank, depth).is_none());
+        Self { text, suffix_array, ranks: start..ranks.end, depth }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Ord, S: SuffixArray + ?Sized> Iterator for Children<'a, T, S> {
+    type Item = (&'a T, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ranks.is_empty() {
+            return None;
+        }
+        let (text, suffix_array, depth) = (self.text, self.suffix_array, self.depth);
+        let lo = self.ranks.start;
+        let key = symbol_at(text, suffix_array, lo, depth);
+        let hi = partition_point(lo, self.ranks.end, |rank| symbol_at(text, suffix_array, rank, depth) <= key);
+        self.ranks.start = hi;
+        key.map(|symbol| (symbol, lo..hi))
+    }
+}
+
+/// Text positions of the suffixes in a range of suffix array indices.
+#[derive(Clone, Debug)]
+pub struct Positions<'a, S: ?Sized> {
+    suffix_array: &'a S,
+    ranks: Range<usize>,
+}
+
+impl<'a, S: SuffixArray + ?Sized> Positions<'a, S> {
+    /// Iterates the text positions of the suffixes at `ranks` of the suffix array.
+    pub fn new(suffix_array: &'a S, ranks: Range<usize>) -> Self {
+        Self { suffix_array, ranks }
+    }
+    /// Remaining range of suffix array indices.
+    pub fn ranks(&self) -> Range<usize> {
+        self.ranks.clone()
+    }
+}
+
+impl<S: SuffixArray + ?Sized> Iterator for Positions<'_, S> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.ranks.next().map(|rank| self.suffix_array.position(rank))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ranks.size_hint()
+    }
+}
+
+impl<S: SuffixArray + ?Sized> DoubleEndedIterator for Positions<'_, S> {
+    fn next_back(&mut self) -> Option<usize> {
+        self.ranks.next_back().map(|rank| self.suffix_array.position(rank))
+    }
+}
+
+impl<S: SuffixArray + ?Sized> ExactSizeIterator for Positions<'_, S> {}
+
+/// Search for the suffixes lexicographically between two patterns.  
+/// Input: Vector of symbols & its suffix array and the bounds.  
+/// A suffix starting with a bound pattern is regarded as equal to it, so
+/// `Included` keeps the suffixes starting with the pattern and `Excluded` drops them.
+/// Output: Text positions of the suffixes in the range, in lexicographic order.
+/// The range of suffix array indices is given by [Positions::ranks].
+/// ```rust
+/// use std::ops::Bound;
+/// use libdivsufsort_rs::{divsufsort, search::sa_range_query};
+///
+/// let input_string = b"key1 key2 key3 key4";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let bounds = (Bound::Included(&b"key2"[..]), Bound::Excluded(&b"key4"[..]));
+/// let mut positions: Vec<usize> = sa_range_query(input_string, &suffix_array, bounds.0, bounds.1).collect();
+/// positions.sort();
+/// assert_eq!(positions, vec![5, 10]);
+/// ```
+pub fn sa_range_query<'a, T: Ord, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    suffix_array: &'a S,
+    lo_pattern: Bound<&[T]>,
+    hi_pattern: Bound<&[T]>,
+) -> Positions<'a, S> {
+    let start = match lo_pattern {
+        Bound::Included(pattern) => lower_bound(input_string, pattern, suffix_array),
+        Bound::Excluded(pattern) => upper_bound(input_string, pattern, suffix_array, 0),
+        Bound::Unbounded => 0,
+    };
+    let end = match hi_pattern {
+        Bound::Included(pattern) => upper_bound(input_string, pattern, suffix_array, start),
+        Bound::Excluded(pattern) => lower_bound(input_string, pattern, suffix_array),
+        Bound::Unbounded => suffix_array.len(),
+    };
+    Positions::new(suffix_array, start..end.max(start))
+}
+
+/// The longest prefix of a pattern occurring in the text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefixMatch {
+    /// Length of the longest prefix of the pattern occurring in the text.
+    pub length: usize,
+    /// Range of suffix array indices whose suffixes start with the prefix.
+    pub ranks: Range<usize>,
+}
+
+/// Search for the longest prefix of the pattern P occurring in the string T.  
+/// Input: Vector of symbols & vector of pattern string and its suffix array.  
+/// Output: Length of the longest prefix and the range of suffix array indices of its occurrences.
+/// The whole pattern occurs if the length is the length of the pattern.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort64, search::sa_longest_prefix_match};
+///
+/// let input_string = b"abracadabra";
+/// let suffix_array = divsufsort64(input_string).unwrap();
+/// let prefix_match = sa_longest_prefix_match(input_string, b"abrasive", &suffix_array);
+/// assert_eq!(prefix_match.length, 4); // "abra"
+/// assert_eq!(prefix_match.ranks.len(), 2);
+/// ```
+pub fn sa_longest_prefix_match<T: Ord, S: SuffixArray + ?Sized>(
+    input_string: &[T],
+    pattern: &[T],
+    suffix_array: &S,
+) -> PrefixMatch {
+    let mut ranks = 0..suffix_array.len();
+    for (depth, symbol) in pattern.iter().enumerate() {
+        let narrowed = narrow(input_string, suffix_array, ranks.clone(), depth, symbol);
+        if narrowed.is_empty() {
+            return PrefixMatch { length: depth, ranks };
+        }
+        ranks = narrowed;
+    }
+    PrefixMatch { length: pattern.len(), ranks }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCCATCCGCTGCCGGTGGATCCGGCTCCTGCGTGGAAAACCAGTCATCCTGATTTACATATGGTTCAATGGCACCGGATGCATAGATTTCCCCATTTTGCGTACCGGAAACGTGCGCAAGCACGATCTGTGTCTTACC";
+
+    #[test]
+    fn test_sa_search_range() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        let suffixes = |range: Range<usize>| range.map(|i| &INPUT[suffix_array[i] as usize..]);
+
+        for pattern in [&b"TACACCTGTTTCG"[..], b"CG", b"A", b"", b"AAAAA", b"ZZ", b"0", b"CCCCC", b"GTA"] {
+            let result = sa_search_range(INPUT, pattern, &suffix_array);
+            assert_eq!(result, sa_search_range(INPUT, pattern, &suffix_array_64));
+            match result {
+                Ok(range) => {
+                    let (idx, count) = crate::sa_search(INPUT, pattern, &suffix_array).unwrap();
+                    assert_eq!(range.len(), count as usize);
+                    assert!(count == 0 || range.start == idx as usize);
+                    assert!(suffixes(range).all(|suffix| suffix.starts_with(pattern)));
+                }
+                Err(rank) => {
+                    assert!(!INPUT.windows(pattern.len()).any(|w| w == pattern));
+                    assert!(suffixes(0..rank).all(|suffix| suffix < pattern));
+                    assert!(suffixes(rank..INPUT.len()).all(|suffix| suffix > pattern));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sa_range_query() {
+        let suffix_array = crate::divsufsort64(INPUT).unwrap();
+        let patterns = [&b"CG"[..], b"CGT", b"G", b"GA", b"TT", b"AAAAA", b""];
+        let in_lo = |suffix: &[u8], bound: Bound<&[u8]>| match bound {
+            Bound::Included(p) => suffix >= p,
+            Bound::Excluded(p) => suffix > p && !suffix.starts_with(p),
+            Bound::Unbounded => true,
+        };
+        let in_hi = |suffix: &[u8], bound: Bound<&[u8]>| match bound {
+            Bound::Included(p) => suffix < p || suffix.starts_with(p),
+            Bound::Excluded(p) => suffix < p,
+            Bound::Unbounded => true,
+        };
+        for &lo in &patterns {
+            for &hi in &patterns {
+                for (lo, hi) in [
+                    (Bound::Included(lo), Bound::Excluded(hi)),
+                    (Bound::Excluded(lo), Bound::Included(hi)),
+                    (Bound::Included(lo), Bound::Included(hi)),
+                    (Bound::Unbounded, Bound::Excluded(hi)),
+                    (Bound::Excluded(lo), Bound::Unbounded),
+                ] {
+                    let positions = sa_range_query(INPUT, &suffix_array, lo, hi);
+                    let ranks = positions.ranks();
+                    let mut positions: Vec<usize> = positions.collect();
+                    assert_eq!(positions.len(), ranks.len());
+                    let mut expected: Vec<usize> = (0..INPUT.len())
+                        .filter(|&p| in_lo(&INPUT[p..], lo) && in_hi(&INPUT[p..], hi))
+                        .collect();
+                    positions.sort_unstable();
+                    expected.sort_unstable();
+                    assert_eq!(positions, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sa_longest_prefix_match() {
+        let suffix_array = crate::divsufsort(INPUT).unwrap();
+        let suffix_array_64 = crate::divsufsort64(INPUT).unwrap();
+        for pattern in [&b"TACACCTGTTTCGXX"[..], b"CGTAZ", b"GATTTCCCCATTTTGCGTAA", b"Z", b"", b"TTTT"] {
+            let prefix_match = sa_longest_prefix_match(INPUT, pattern, &suffix_array);
+            assert_eq!(prefix_match, sa_longest_prefix_match(INPUT, pattern, &suffix_array_64));
+
+            let occurs = |length: usize| INPUT.windows(length).any(|w| w == &pattern[..length]);
+            let expected_length = (0..=pattern.len()).rev().find(|&length| length == 0 || occurs(length)).unwrap();
+            assert_eq!(prefix_match.length, expected_length);
+            let prefix = &pattern[..prefix_match.length];
+            assert_eq!(Ok(prefix_match.ranks), sa_search_range(INPUT, prefix, &suffix_array));
+        }
+    }
+}
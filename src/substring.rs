@@ -0,0 +1,117 @@
+//! Statistics of the distinct substrings with the suffix array and the LCP array.
+//!
+//! In suffix array order, the suffix at rank `i` adds the prefixes longer than `lcp[i]` as new distinct
+//! substrings, in lexicographic order. A text of `n` symbols has up to `n (n + 1) / 2` distinct substrings,
+//! so the total is counted in `u128`, which cannot overflow for the 64-bit suffix arrays.
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::sa::SaIndex;
+
+/// Number of distinct non-empty substrings of a text.  
+/// Input: Suffix array & LCP array of the text (see [lcp_array](crate::lcp::lcp_array)).
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, lcp::lcp_array, substring::distinct_substring_count};
+///
+/// let input_string = b"abab";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let lcp = lcp_array(input_string, &suffix_array);
+/// // a, b, ab, ba, aba, bab, abab
+/// assert_eq!(distinct_substring_count(&suffix_array, &lcp), 7);
+/// ```
+pub fn distinct_substring_count<I: SaIndex>(suffix_array: &[I], lcp: &[I]) -> u128 {
+    assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+    let n = suffix_array.len();
+    suffix_array
+        .iter()
+        .zip(lcp)
+        .map(|(&position, &common)| (n - position.to_usize() - common.to_usize()) as u128)
+        .sum()
+}
+
+/// Numbers of distinct substrings of each length.  
+/// Input: Suffix array & LCP array of the text.  
+/// Output: Vector of length `n + 1` where `counts[length]` is the number of distinct substrings of `length` symbols,  
+/// and `counts[0]` is 0.
+pub fn distinct_substring_counts_by_length<I: SaIndex>(suffix_array: &[I], lcp: &[I]) -> Vec<usize> {
+    assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+    let n = suffix_array.len();
+    // The suffix at rank `i` adds one substring of each length in `lcp[i] + 1..=n - suffix_array[i]`
+    let mut differences = vec![0isize; n + 2];
+    for (&position, &common) in suffix_array.iter().zip(lcp) {
+        differences[common.to_usize() + 1] += 1;
+        differences[n - position.to_usize() + 1] -= 1;
+    }
+    let mut counts = Vec::with_capacity(n + 1);
+    counts.push(0);
+    let mut count = 0;
+    for &difference in &differences[1..=n] {
+        count += difference;
+        counts.push(count as usize);
+    }
+    counts
+}
+
+/// The `k`-th (0-based) lexicographically smallest distinct non-empty substring.  
+/// Input: Suffix array & LCP array of the text and `k`.  
+/// Output: Range of the text of an occurrence of the substring, `None` if `k` is not less than the number of distinct substrings.
+/// ```rust
+/// use libdivsufsort_rs::{divsufsort, lcp::lcp_array, substring::kth_substring};
+///
+/// let input_string = b"abab";
+/// let suffix_array = divsufsort(input_string).unwrap();
+/// let lcp = lcp_array(input_string, &suffix_array);
+/// // a < ab < aba < abab < b < ba < bab
+/// let range = kth_substring(&suffix_array, &lcp, 5).unwrap();
+/// assert_eq!(&input_string[range], b"ba");
+/// assert_eq!(kth_substring(&suffix_array, &lcp, 7), None);
+/// ```
+pub fn kth_substring<I: SaIndex>(suffix_array: &[I], lcp: &[I], mut k: u128) -> Option<Range<usize>> {
+    assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+    let n = suffix_array.len();
+    for (&position, &common) in suffix_array.iter().zip(lcp) {
+        let (position, common) = (position.to_usize(), common.to_usize());
+        let added = (n - position - common) as u128;
+        if k < added {
+            return Some(position..position + common + 1 + k as usize);
+        }
+        k -= added;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeSet;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGGAAACCGTATATTGTCC";
+
+    #[test]
+    fn test_distinct_substrings() {
+        for input_string in [INPUT, b"AAAAAAAAAA", b"A", b""] {
+            let suffix_array = crate::divsufsort64(input_string).unwrap();
+            let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+            let mut substrings = BTreeSet::new();
+            for start in 0..input_string.len() {
+                for end in start + 1..=input_string.len() {
+                    substrings.insert(&input_string[start..end]);
+                }
+            }
+            assert_eq!(distinct_substring_count(&suffix_array, &lcp), substrings.len() as u128);
+
+            let mut counts = vec![0; input_string.len() + 1];
+            for substring in &substrings {
+                counts[substring.len()] += 1;
+            }
+            assert_eq!(distinct_substring_counts_by_length(&suffix_array, &lcp), counts);
+
+            for (k, &substring) in substrings.iter().enumerate() {
+                let range = kth_substring(&suffix_array, &lcp, k as u128).unwrap();
+                assert_eq!(&input_string[range], substring);
+            }
+            assert_eq!(kth_substring(&suffix_array, &lcp, substrings.len() as u128), None);
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Compact suffix tree constructed from the suffix array and the LCP array.
+//!
+//! The tree is built in one pass over the suffix array, keeping the rightmost path on a stack.
+//! Like [esa](crate::esa), texts are not terminated by a sentinel: a suffix that is a prefix of another suffix
+//! is a leaf with an empty edge label.
+
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt::Write;
+use core::ops::Range;
+
+use crate::sa::SaIndex;
+
+/// Index of a node in [SuffixTree::nodes].
+pub type NodeId = usize;
+
+const NONE: usize = usize::MAX;
+
+/// A node of the suffix tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    /// Length of the path label from the root.
+    pub depth: usize,
+    /// Range of suffix array indices of the leaves below the node.
+    pub ranks: Range<usize>,
+    pub parent: Option<NodeId>,
+    /// For a node labeled `cα`, the node labeled `α`. `None` for the root and the leaf of the last suffix.
+    pub suffix_link: Option<NodeId>,
+    first_child: usize,
+    next_sibling: usize,
+}
+
+impl Node {
+    fn new(depth: usize, ranks: Range<usize>, parent: Option<NodeId>) -> Self {
+        Self { depth, ranks, parent, suffix_link: None, first_child: NONE, next_sibling: NONE }
+    }
+    /// Whether the node is a leaf, i.e. a single suffix.
+    pub fn is_leaf(&self) -> bool {
+        self.first_child == NONE
+    }
+}
+
+/// Suffix tree with suffix links, as nodes referring to ranges of the suffix array.
+#[derive(Clone, Debug)]
+pub struct SuffixTree<I: SaIndex = i32> {
+    suffix_array: Vec<I>,
+    /// The root is the first node.
+    nodes: Vec<Node>,
+}
+
+impl SuffixTree<i32> {
+    /// Constructs the suffix tree with [divsufsort](crate::divsufsort).
+    pub fn new(input_string: &[u8]) -> Option<Self> {
+        let suffix_array = crate::divsufsort(input_string)?;
+        let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+        Some(Self::from_parts(suffix_array, &lcp))
+    }
+}
+
+impl SuffixTree<i64> {
+    /// 64-bit version of [new](SuffixTree::new)
+    pub fn new64(input_string: &[u8]) -> Option<Self> {
+        let suffix_array = crate::divsufsort64(input_string)?;
+        let lcp = crate::lcp::lcp_array(input_string, &suffix_array);
+        Some(Self::from_parts(suffix_array, &lcp))
+    }
+}
+
+impl<I: SaIndex> SuffixTree<I> {
+    /// Constructs the suffix tree of a suffix array and its LCP array (see [lcp_array](crate::lcp::lcp_array))  
+    /// with suffix links in `O(n)` time.
+    pub fn from_parts(suffix_array: Vec<I>, lcp: &[I]) -> Self {
+        assert_eq!(suffix_array.len(), lcp.len(), "lengths of the suffix array and the LCP array are different");
+        let n = suffix_array.len();
+        let mut nodes = vec![Node::new(0, 0..n, None)];
+        // Children of each node, linked as siblings after the construction
+        let mut children: Vec<Vec<NodeId>> = vec![Vec::new()];
+        let mut leaves = Vec::with_capacity(n);
+
+        let mut stack: Vec<NodeId> = vec![0];
+        for i in 0..n {
+            let common = if i == 0 { 0 } else { lcp[i].to_usize() };
+            let mut top = *stack.last().unwrap();
+            while nodes[top].depth > common {
+                nodes[top].ranks.end = i;
+                stack.pop();
+                top = *stack.last().unwrap();
+            }
+            if nodes[top].depth < common {
+                // Split the edge to the last child of `top`, which is the last popped node or the previous leaf
+                let node = nodes.len();
+                let child = children[top].pop().unwrap();
+                nodes.push(Node::new(common, nodes[child].ranks.start..n, Some(top)));
+                children.push(vec![child]);
+                nodes[child].parent = Some(node);
+                children[top].push(node);
+                stack.push(node);
+                top = node;
+            }
+            let leaf = nodes.len();
+            nodes.push(Node::new(n - suffix_array[i].to_usize(), i..i + 1, Some(top)));
+            children.push(Vec::new());
+            children[top].push(leaf);
+            leaves.push(leaf);
+        }
+        for &node in &stack {
+            nodes[node].ranks.end = n;
+        }
+        for (node, children) in children.into_iter().enumerate() {
+            if let Some(&first) = children.first() {
+                nodes[node].first_child = first;
+            }
+            for pair in children.windows(2) {
+                nodes[pair[0]].next_sibling = pair[1];
+            }
+        }
+
+        let mut tree = Self { suffix_array, nodes };
+        tree.link(&leaves);
+        tree
+    }
+
+    /// Sets the suffix links in `O(n)`.  
+    /// The link of an internal node `cα` is the ancestor at depth `|α|` of the leaf of the suffix after  
+    /// its first leaf. These level ancestor queries are answered in one preorder traversal, keeping the  
+    /// internal nodes on the path from the root by their depths.
+    fn link(&mut self, leaves: &[NodeId]) {
+        let n = self.suffix_array.len();
+        if n == 0 {
+            return;
+        }
+        let mut inverse_suffix_array = vec![0; n];
+        for (rank, &position) in self.suffix_array.iter().enumerate() {
+            inverse_suffix_array[position.to_usize()] = rank;
+        }
+        // Queries of each leaf rank as linked lists of nodes
+        let mut first_query = vec![NONE; n];
+        let mut next_query = vec![NONE; self.nodes.len()];
+        for (id, node) in self.nodes.iter_mut().enumerate().skip(1) {
+            let position = self.suffix_array[node.ranks.start].to_usize() + 1;
+            node.suffix_link = if node.ranks.len() == 1 {
+                // Leaf: the leaf of the next suffix
+                if position < n {
+                    Some(leaves[inverse_suffix_array[position]])
+                } else {
+                    None
+                }
+            } else if node.depth == 1 {
+                Some(0)
+            } else {
+                let rank = inverse_suffix_array[position];
+                next_query[id] = first_query[rank];
+                first_query[rank] = id;
+                None
+            };
+        }
+
+        let mut path = vec![NONE; n + 1];
+        let order: Vec<NodeId> = self.preorder().collect();
+        for id in order {
+            let node = &self.nodes[id];
+            if !node.is_leaf() {
+                path[node.depth] = id;
+                continue;
+            }
+            let mut query = first_query[node.ranks.start];
+            while query != NONE {
+                let depth = self.nodes[query].depth - 1;
+                self.nodes[query].suffix_link = Some(path[depth]);
+                query = next_query[query];
+            }
+        }
+    }
+
+    /// Suffix array the tree is built from.
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+    /// All nodes, the root first.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+    /// Id of the root.
+    pub fn root(&self) -> NodeId {
+        0
+    }
+    /// Node with the given id.
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+    /// Children of a node in lexicographic order of their edge labels.
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut child = self.nodes[id].first_child;
+        core::iter::from_fn(move || {
+            if child == NONE {
+                return None;
+            }
+            let current = child;
+            child = self.nodes[child].next_sibling;
+            Some(current)
+        })
+    }
+    /// Nodes in depth-first order, each before its children.
+    pub fn preorder(&self) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![self.root()];
+        core::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let start = stack.len();
+            stack.extend(self.children(node));
+            stack[start..].reverse();
+            Some(node)
+        })
+    }
+    /// Text position of the suffix of a leaf.
+    pub fn position(&self, leaf: NodeId) -> Option<usize> {
+        let node = &self.nodes[leaf];
+        if node.is_leaf() && node.ranks.len() == 1 {
+            Some(self.suffix_array[node.ranks.start].to_usize())
+        } else {
+            None
+        }
+    }
+    /// Range of the text spelling the path label of a node.
+    pub fn path_label(&self, id: NodeId) -> Range<usize> {
+        let node = &self.nodes[id];
+        if node.ranks.is_empty() {
+            return 0..0;
+        }
+        let position = self.suffix_array[node.ranks.start].to_usize();
+        position..position + node.depth
+    }
+    /// Range of the text spelling the label of the edge from the parent, empty for the root.
+    pub fn edge_label(&self, id: NodeId) -> Range<usize> {
+        let node = &self.nodes[id];
+        match node.parent {
+            Some(parent) => {
+                let label = self.path_label(id);
+                label.start + self.nodes[parent].depth..label.end
+            }
+            None => 0..0,
+        }
+    }
+
+    /// Exports the tree in the DOT language of Graphviz.  
+    /// Internal nodes are labeled with their depths and leaves with their text positions.  
+    /// Suffix links of internal nodes are drawn as dashed edges.
+    /// ```rust
+    /// use libdivsufsort_rs::suffix_tree::SuffixTree;
+    ///
+    /// let input_string = b"banana";
+    /// let tree = SuffixTree::new(input_string).unwrap();
+    /// let dot = tree.to_dot(input_string);
+    /// assert!(dot.starts_with("digraph"));
+    /// assert!(dot.contains("label=\"na\""));
+    /// ```
+    pub fn to_dot(&self, input_string: &[u8]) -> String {
+        let mut dot = String::from("digraph suffix_tree {\n");
+        for id in self.preorder() {
+            let node = &self.nodes[id];
+            let _ = match self.position(id) {
+                Some(position) => writeln!(dot, "  n{} [shape=box, label=\"{}\"];", id, position),
+                None => writeln!(dot, "  n{} [shape=circle, label=\"{}\"];", id, node.depth),
+            };
+            if let Some(parent) = node.parent {
+                let label: String = input_string[self.edge_label(id)]
+                    .iter()
+                    .flat_map(|&c| core::ascii::escape_default(c))
+                    .map(char::from)
+                    .collect();
+                let _ = writeln!(dot, "  n{} -> n{} [label=\"{}\"];", parent, id, label);
+            }
+            if let (false, Some(link)) = (node.is_leaf(), node.suffix_link) {
+                let _ = writeln!(dot, "  n{} -> n{} [style=dashed, constraint=false];", id, link);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esa::EnhancedSuffixArray;
+
+    const INPUT: &[u8] = b"CTCCGTACACCTGTTTCGTATCGGAACCGGTAAGTGAAATTTCCACATCGCCGG";
+
+    #[test]
+    fn test_suffix_tree() {
+        for input_string in [INPUT, b"AAAAAA", b"ABABABA", b"mississippi", b"A", b""] {
+            let tree = SuffixTree::new(input_string).unwrap();
+            assert_eq!(tree.nodes(), SuffixTree::new64(input_string).unwrap().nodes());
+            let label = |id: NodeId| &input_string[tree.path_label(id)];
+
+            let mut positions = Vec::new();
+            let mut internal: Vec<(usize, Range<usize>)> = Vec::new();
+            for id in tree.preorder() {
+                let node = tree.node(id);
+                if let Some(parent) = node.parent {
+                    assert!(node.depth > tree.node(parent).depth || (node.is_leaf() && node.depth == tree.node(parent).depth));
+                    assert_eq!(label(parent), &label(id)[..tree.node(parent).depth]);
+                }
+                match tree.position(id) {
+                    Some(position) => {
+                        positions.push(position);
+                        assert_eq!(label(id), &input_string[position..]);
+                    }
+                    None => internal.push((node.depth, node.ranks.clone())),
+                }
+                // Children are sorted by their first symbols, and an empty edge comes first
+                let first_symbols: Vec<Option<u8>> =
+                    tree.children(id).map(|child| input_string[tree.edge_label(child)].first().copied()).collect();
+                assert!(first_symbols.windows(2).all(|w| w[0] < w[1]));
+                if let Some(link) = node.suffix_link {
+                    assert_eq!(label(link), &label(id)[1..]);
+                } else {
+                    assert!(id == tree.root() || tree.position(id) == Some(input_string.len() - 1));
+                }
+            }
+            positions.sort_unstable();
+            assert_eq!(positions, (0..input_string.len()).collect::<Vec<_>>());
+
+            // Internal nodes are the lcp-intervals and the root
+            let esa = EnhancedSuffixArray::new(input_string).unwrap();
+            let mut expected: Vec<(usize, Range<usize>)> =
+                esa.intervals_bottom_up().map(|interval| (interval.depth, interval.ranks)).collect();
+            if expected.iter().all(|(depth, _)| *depth > 0) {
+                expected.push((0, 0..input_string.len()));
+            }
+            let key = |(depth, ranks): &(usize, Range<usize>)| (ranks.start, usize::MAX - ranks.end, *depth);
+            internal.sort_unstable_by_key(key);
+            expected.sort_unstable_by_key(key);
+            assert_eq!(internal, expected);
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = SuffixTree::new(b"abab").unwrap();
+        let dot = tree.to_dot(b"abab");
+        assert_eq!(dot.matches("shape=box").count(), 4);
+        assert_eq!(dot.matches("style=dashed").count(), 2);
+        assert!(dot.contains("label=\"ab\""));
+    }
+}
@@ -0,0 +1,19 @@
+//! Helpers shared by the unit tests.
+
+/// Linear congruential generator (Knuth's MMIX constants) for reproducible random inputs.
+pub(crate) struct Lcg(u64);
+
+impl Lcg {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    /// Next state of the generator. The high bits are the most random.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0
+    }
+    /// A number in `0..bound`.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() >> 33) as usize % bound
+    }
+}
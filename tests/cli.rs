@@ -0,0 +1,98 @@
+//! Round trips through the command-line tools, checking the file formats of `libdivsufsort`'s examples.
+#![cfg(feature = "cli")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A file in the temporary directory, removed on drop.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> Self {
+        Self(env::temp_dir().join(format!("libdivsufsort-rs-{}-{}", std::process::id(), name)))
+    }
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Vec<u8> {
+    let output = Command::new(program).args(args).output().unwrap();
+    assert!(output.status.success(), "{} failed: {}", program, String::from_utf8_lossy(&output.stderr));
+    output.stdout
+}
+
+fn sample_text(length: usize) -> Vec<u8> {
+    let mut seed = 3u64;
+    (0..length)
+        .map(|_| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            b"ACGT"[(seed >> 62) as usize]
+        })
+        .collect()
+}
+
+#[test]
+fn test_mksary_and_search() {
+    let text = sample_text(10000);
+    let (input, sa_file) = (TempFile::new("mksary.txt"), TempFile::new("mksary.sa"));
+    fs::write(input.path(), &text).unwrap();
+    run(env!("CARGO_BIN_EXE_mksary"), &[input.path(), sa_file.path()]);
+
+    // `saidx_t` in native byte order, without a header
+    let expected: Vec<u8> = libdivsufsort_rs::divsufsort(&text).unwrap().iter().flat_map(|v| v.to_ne_bytes()).collect();
+    assert_eq!(fs::read(sa_file.path()).unwrap(), expected);
+
+    run(env!("CARGO_BIN_EXE_suftest"), &[input.path()]);
+    let output = run(env!("CARGO_BIN_EXE_sasearch"), &["ACGTA", input.path(), sa_file.path()]);
+    let mut positions: Vec<usize> =
+        String::from_utf8(output).unwrap().lines().map(|line| line.parse().unwrap()).collect();
+    positions.sort_unstable();
+    let naive: Vec<usize> = (0..text.len()).filter(|&i| text[i..].starts_with(b"ACGTA")).collect();
+    assert_eq!(positions, naive);
+}
+
+#[test]
+fn test_bwt_and_unbwt() {
+    // Two blocks of 1 MiB
+    let block_size = 1 << 20;
+    let text = sample_text(block_size + 1000);
+    let (input, transformed, restored) = (TempFile::new("bwt.txt"), TempFile::new("bwt.bwt"), TempFile::new("bwt.out"));
+    fs::write(input.path(), &text).unwrap();
+    run(env!("CARGO_BIN_EXE_bwt"), &["-b", "1", input.path(), transformed.path()]);
+
+    // The block size, then a little-endian primary index before each block
+    let output = fs::read(transformed.path()).unwrap();
+    assert_eq!(output.len(), 4 + 2 * 4 + text.len());
+    assert_eq!(output[..4], (block_size as i32).to_le_bytes());
+    let mut offset = 4;
+    for block in text.chunks(block_size) {
+        let mut expected = block.to_vec();
+        let primary_index = libdivsufsort_rs::divbwt(&mut expected).unwrap();
+        assert_eq!(output[offset..offset + 4], primary_index.to_le_bytes());
+        assert_eq!(output[offset + 4..offset + 4 + block.len()], expected[..]);
+        offset += 4 + block.len();
+    }
+
+    run(env!("CARGO_BIN_EXE_unbwt"), &[transformed.path(), restored.path()]);
+    assert_eq!(fs::read(restored.path()).unwrap(), text);
+}
+
+#[test]
+fn test_bwt_empty_input() {
+    let (input, transformed, restored) = (TempFile::new("empty.txt"), TempFile::new("empty.bwt"), TempFile::new("empty.out"));
+    fs::write(input.path(), b"").unwrap();
+    run(env!("CARGO_BIN_EXE_bwt"), &[input.path(), transformed.path()]);
+    // Only the block size 0
+    assert_eq!(fs::read(transformed.path()).unwrap(), 0i32.to_le_bytes());
+    run(env!("CARGO_BIN_EXE_unbwt"), &[transformed.path(), restored.path()]);
+    assert!(fs::read(restored.path()).unwrap().is_empty());
+}